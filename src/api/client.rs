@@ -1,3 +1,8 @@
+use crate::api::rate_limit::RateLimits;
+use crate::api::requests::request_api;
+use crate::api::retry::RetryPolicy;
+use crate::api::urls::BaseUrls;
+use crate::api::v2;
 use crate::auth::user_auth::TwitterAuth;
 use crate::error::{Result, TwitterError};
 use crate::models::Tweet;
@@ -8,19 +13,45 @@ use std::time::Duration;
 pub struct TwitterClient {
     pub client: Client,
     pub auth: Box<dyn TwitterAuth + Send + Sync>,
+    pub urls: BaseUrls,
+    pub retry_policy: RetryPolicy,
+    pub rate_limits: RateLimits,
 }
 
 impl TwitterClient {
     pub fn new(auth: Box<dyn TwitterAuth + Send + Sync>) -> Result<Self> {
+        // Cookies live in the auth jar, which sets the Cookie header itself.
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
-            .cookie_store(true)
             .build()?;
 
-        Ok(Self { client, auth })
+        Ok(Self::from_http_client(client, auth))
+    }
+
+    /// Wraps an already configured `reqwest::Client` instead of building one.
+    pub fn from_http_client(client: Client, auth: Box<dyn TwitterAuth + Send + Sync>) -> Self {
+        Self {
+            client,
+            auth,
+            urls: BaseUrls::default(),
+            retry_policy: RetryPolicy::default(),
+            rate_limits: RateLimits::new(),
+        }
+    }
+
+    pub fn with_base_urls(mut self, urls: BaseUrls) -> Self {
+        self.urls = urls;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
     }
 
     pub async fn send_tweet(&self, text: &str, media_ids: Option<Vec<String>>) -> Result<Tweet> {
+        crate::text::validate_tweet(text, media_ids.as_ref().is_some_and(|ids| !ids.is_empty()))?;
+
         let mut params = serde_json::json!({
             "text": text,
         });
@@ -29,22 +60,44 @@ impl TwitterClient {
             params["media"] = serde_json::json!({ "media_ids": ids });
         }
 
-        let endpoint = "https://api.twitter.com/2/tweets";
-        self.post(endpoint, Some(params)).await
+        let endpoint = self.urls.api_url("/2/tweets");
+        let response: serde_json::Value = self.post(&endpoint, Some(params)).await?;
+        v2::parse_tweet_response(response)
     }
 
     pub async fn get_tweet(&self, tweet_id: &str) -> Result<Tweet> {
-        let endpoint = format!("https://api.twitter.com/2/tweets/{}", tweet_id);
-        self.get(&endpoint).await
+        let response = v2::TweetLookup::new([tweet_id]).send(self).await?;
+        response
+            .data
+            .into_iter()
+            .next()
+            .ok_or_else(|| TwitterError::Api(format!("Tweet {} not found", tweet_id)))
     }
 
+    /// Fetches up to `limit` of the user's most recent tweets, following
+    /// `next_token` across pages as needed.
     pub async fn get_user_tweets(&self, user_id: &str, limit: usize) -> Result<Vec<Tweet>> {
-        let endpoint = format!("https://api.twitter.com/2/users/{}/tweets", user_id);
-        let params = serde_json::json!({
-            "max_results": limit,
-            "tweet.fields": "created_at,author_id,conversation_id,public_metrics"
-        });
-        self.get_with_params(&endpoint, Some(params)).await
+        let mut tweets = Vec::new();
+        let mut next_token: Option<String> = None;
+
+        while tweets.len() < limit {
+            // The endpoint only accepts page sizes between 5 and 100.
+            let page_size = (limit - tweets.len()).clamp(5, 100) as u32;
+            let mut request = v2::UserTimeline::new(user_id).with_max_results(page_size);
+            if let Some(token) = next_token.take() {
+                request = request.with_pagination_token(token);
+            }
+
+            let response = request.send(self).await?;
+            tweets.extend(response.data);
+            match response.meta.next_token {
+                Some(token) => next_token = Some(token),
+                None => break,
+            }
+        }
+
+        tweets.truncate(limit);
+        Ok(tweets)
     }
 
     pub async fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
@@ -67,6 +120,13 @@ impl TwitterClient {
         self.request(Method::POST, endpoint, params).await
     }
 
+    /// Installs auth headers, first letting the auth refresh anything stale
+    /// such as an expired guest token.
+    pub async fn install_headers(&self, headers: &mut reqwest::header::HeaderMap) -> Result<()> {
+        self.auth.prepare(self).await?;
+        self.auth.install_headers(headers).await
+    }
+
     pub async fn request<T: DeserializeOwned>(
         &self,
         method: Method,
@@ -74,24 +134,9 @@ impl TwitterClient {
         params: Option<serde_json::Value>,
     ) -> Result<T> {
         let mut headers = reqwest::header::HeaderMap::new();
-        self.auth.install_headers(&mut headers).await?;
-
-        let mut request = self.client.request(method, endpoint);
-        request = request.headers(headers);
+        self.install_headers(&mut headers).await?;
 
-        if let Some(params) = params {
-            request = request.json(&params);
-        }
-
-        let response = request.send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(TwitterError::Api(format!(
-                "Request failed with status: {}",
-                response.status()
-            )))
-        }
+        let (value, _) = request_api(self, endpoint, headers, method, params).await?;
+        Ok(value)
     }
 }
@@ -1,3 +1,4 @@
+use crate::api::urls::BaseUrls;
 use std::collections::HashMap;
 use urlencoding;
 
@@ -36,6 +37,33 @@ pub const DEFAULT_TWEET_FIELDS: &[&str] = &[
     "note_tweet",
 ];
 
+pub const DEFAULT_USER_FIELDS: &[&str] = &[
+    "created_at",
+    "description",
+    "location",
+    "pinned_tweet_id",
+    "profile_image_url",
+    "protected",
+    "public_metrics",
+    "url",
+    "verified",
+];
+
+pub const DEFAULT_MEDIA_FIELDS: &[&str] = &[
+    "alt_text",
+    "duration_ms",
+    "height",
+    "preview_image_url",
+    "type",
+    "url",
+    "variants",
+    "width",
+];
+
+pub const DEFAULT_POLL_FIELDS: &[&str] = &["duration_minutes", "end_datetime", "options", "voting_status"];
+
+pub const DEFAULT_PLACE_FIELDS: &[&str] = &["country", "country_code", "full_name", "geo", "name", "place_type"];
+
 #[derive(Debug, Clone)]
 pub struct ApiEndpoint {
     pub url: String,
@@ -80,9 +108,9 @@ impl ApiEndpoint {
 pub struct Endpoints;
 
 impl Endpoints {
-    pub fn tweet_detail(tweet_id: &str) -> ApiEndpoint {
+    pub fn tweet_detail(urls: &BaseUrls, tweet_id: &str) -> ApiEndpoint {
         ApiEndpoint {
-            url: "https://twitter.com/i/api/graphql/xOhkmRac04YFZmOzU9PJHg/TweetDetail".to_string(),
+            url: urls.graphql_url("/xOhkmRac04YFZmOzU9PJHg/TweetDetail"),
             variables: Some(HashMap::from([
                 ("focalTweetId".to_string(), tweet_id.into()),
                 ("with_rux_injections".to_string(), false.into()),
@@ -155,10 +183,9 @@ impl Endpoints {
         }
     }
 
-    pub fn tweet_by_rest_id(tweet_id: &str) -> ApiEndpoint {
+    pub fn tweet_by_rest_id(urls: &BaseUrls, tweet_id: &str) -> ApiEndpoint {
         ApiEndpoint {
-            url: "https://twitter.com/i/api/graphql/DJS3BdhUhcaEpZ7B7irJDg/TweetResultByRestId"
-                .to_string(),
+            url: urls.graphql_url("/DJS3BdhUhcaEpZ7B7irJDg/TweetResultByRestId"),
             variables: Some(HashMap::from([
                 ("tweetId".to_string(), tweet_id.into()),
                 ("withCommunity".to_string(), false.into()),
@@ -193,7 +220,12 @@ impl Endpoints {
         }
     }
 
-    pub fn user_tweets(user_id: &str, count: i32, cursor: Option<&str>) -> ApiEndpoint {
+    pub fn user_tweets(
+        urls: &BaseUrls,
+        user_id: &str,
+        count: i32,
+        cursor: Option<&str>,
+    ) -> ApiEndpoint {
         let mut variables = HashMap::from([
             ("userId".to_string(), user_id.into()),
             ("count".to_string(), count.into()),
@@ -211,7 +243,7 @@ impl Endpoints {
         }
 
         ApiEndpoint {
-            url: "https://twitter.com/i/api/graphql/V7H0Ap3_Hh2FyS75OCDO3Q/UserTweets".to_string(),
+            url: urls.graphql_url("/V7H0Ap3_Hh2FyS75OCDO3Q/UserTweets"),
             variables: Some(variables),
             features: Some(HashMap::from([
                 ("rweb_tipjar_consumption_enabled".to_string(), true),
@@ -280,7 +312,12 @@ impl Endpoints {
         }
     }
 
-    pub fn user_tweets_and_replies(user_id: &str, count: i32, cursor: Option<&str>) -> ApiEndpoint {
+    pub fn user_tweets_and_replies(
+        urls: &BaseUrls,
+        user_id: &str,
+        count: i32,
+        cursor: Option<&str>,
+    ) -> ApiEndpoint {
         let mut variables = HashMap::from([
             ("userId".to_string(), user_id.into()),
             ("count".to_string(), count.into()),
@@ -295,8 +332,7 @@ impl Endpoints {
         }
 
         ApiEndpoint {
-            url: "https://twitter.com/i/api/graphql/E4wA5vo2sjVyvpliUffSCw/UserTweetsAndReplies"
-                .to_string(),
+            url: urls.graphql_url("/E4wA5vo2sjVyvpliUffSCw/UserTweetsAndReplies"),
             variables: Some(variables),
             features: Some(HashMap::from([
                 ("rweb_tipjar_consumption_enabled".to_string(), true),
@@ -1,6 +1,13 @@
 pub mod client;
 pub mod endpoints;
+pub mod rate_limit;
 pub mod requests;
+pub mod retry;
+pub mod urls;
+pub mod v2;
 pub use client::TwitterClient;
 pub use endpoints::Endpoints;
+pub use rate_limit::{RateLimitStatus, RateLimits};
+pub use retry::RetryPolicy;
+pub use urls::BaseUrls;
 pub use reqwest::Method;
@@ -0,0 +1,188 @@
+use crate::error::{Result, TwitterError};
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0 && self.reset_at > Utc::now()
+    }
+
+    /// Time left until the window resets, or `None` if calls are allowed now.
+    pub fn wait_time(&self) -> Option<Duration> {
+        if self.is_exhausted() {
+            (self.reset_at - Utc::now()).to_std().ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// Rate limit windows seen in `x-rate-limit-*` headers, keyed by endpoint.
+///
+/// GraphQL calls are keyed by operation name (`SearchTimeline`, `UserTweets`,
+/// `Following`, ...) and REST calls by path (`/1.1/friendships/create.json`).
+/// Clones share the same registry.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimits {
+    endpoints: Arc<Mutex<HashMap<String, RateLimitStatus>>>,
+}
+
+impl RateLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, endpoint: &str) -> Option<RateLimitStatus> {
+        self.endpoints.lock().unwrap().get(endpoint).copied()
+    }
+
+    pub fn all(&self) -> HashMap<String, RateLimitStatus> {
+        self.endpoints.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.endpoints.lock().unwrap().clear();
+    }
+
+    /// Sleeps until `endpoint` has calls left in its current window.
+    pub async fn wait_until_available(&self, endpoint: &str) {
+        if let Some(wait) = self.get(endpoint).and_then(|status| status.wait_time()) {
+            tracing::debug!(endpoint, ?wait, "Waiting for rate limit window to reset");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Waits for an exhausted window if it resets within `max_wait`, otherwise
+    /// fails with `TwitterError::RateLimit` without sending the request.
+    pub(crate) async fn acquire(&self, endpoint: &str, max_wait: Duration) -> Result<()> {
+        let Some(status) = self.get(endpoint) else {
+            return Ok(());
+        };
+        match status.wait_time() {
+            Some(wait) if wait > max_wait => Err(TwitterError::RateLimit {
+                reset_at: Some(status.reset_at),
+            }),
+            Some(_) => {
+                self.wait_until_available(endpoint).await;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn record(&self, endpoint: &str, headers: &HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<i64>().ok())
+        };
+
+        let (Some(limit), Some(remaining), Some(reset)) = (
+            header("x-rate-limit-limit"),
+            header("x-rate-limit-remaining"),
+            header("x-rate-limit-reset"),
+        ) else {
+            return;
+        };
+        let Some(reset_at) = Utc.timestamp_opt(reset, 0).single() else {
+            return;
+        };
+
+        self.endpoints.lock().unwrap().insert(
+            endpoint.to_string(),
+            RateLimitStatus {
+                limit: limit.max(0) as u32,
+                remaining: remaining.max(0) as u32,
+                reset_at,
+            },
+        );
+    }
+}
+
+/// Registry key for a request URL: the GraphQL operation name when the path
+/// goes through `/graphql/`, otherwise the URL path.
+pub fn endpoint_key(url: &str) -> String {
+    let path = url::Url::parse(url)
+        .map(|parsed| parsed.path().to_string())
+        .unwrap_or_else(|_| url.split('?').next().unwrap_or(url).to_string());
+
+    if path.contains("/graphql/") {
+        if let Some(operation) = path.rsplit('/').next().filter(|s| !s.is_empty()) {
+            return operation.to_string();
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn limit_headers(limit: u32, remaining: u32, reset: i64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rate-limit-limit", HeaderValue::from(limit));
+        headers.insert("x-rate-limit-remaining", HeaderValue::from(remaining));
+        headers.insert("x-rate-limit-reset", HeaderValue::from(reset));
+        headers
+    }
+
+    #[test]
+    fn keys_graphql_by_operation_and_rest_by_path() {
+        assert_eq!(
+            endpoint_key("https://x.com/i/api/graphql/abc123/SearchTimeline?variables=%7B%7D"),
+            "SearchTimeline"
+        );
+        assert_eq!(
+            endpoint_key("https://api.twitter.com/1.1/friendships/create.json"),
+            "/1.1/friendships/create.json"
+        );
+    }
+
+    #[test]
+    fn records_headers_per_endpoint() {
+        let limits = RateLimits::new();
+        let reset = Utc::now().timestamp() + 900;
+        limits.record("SearchTimeline", &limit_headers(50, 0, reset));
+        limits.record("UserTweets", &limit_headers(500, 499, reset));
+        limits.record("Following", &HeaderMap::new());
+
+        let search = limits.get("SearchTimeline").unwrap();
+        assert_eq!((search.limit, search.remaining), (50, 0));
+        assert_eq!(search.reset_at.timestamp(), reset);
+        assert!(search.is_exhausted());
+        assert!(search.wait_time().is_some());
+
+        let tweets = limits.get("UserTweets").unwrap();
+        assert_eq!(tweets.remaining, 499);
+        assert!(!tweets.is_exhausted());
+
+        assert!(limits.get("Following").is_none());
+        assert_eq!(limits.all().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn acquire_fails_fast_past_max_wait() {
+        let limits = RateLimits::new();
+        let reset = Utc::now().timestamp() + 900;
+        limits.record("SearchTimeline", &limit_headers(50, 0, reset));
+
+        let err = limits
+            .acquire("SearchTimeline", Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TwitterError::RateLimit { reset_at: Some(_) }));
+        limits.acquire("UserTweets", Duration::ZERO).await.unwrap();
+    }
+}
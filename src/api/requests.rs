@@ -1,10 +1,13 @@
-use crate::error::Result;
+use crate::api::client::TwitterClient;
+use crate::api::rate_limit::endpoint_key;
+use crate::error::{Result, TwitterError};
+use chrono::{DateTime, TimeZone, Utc};
 use reqwest::multipart::Form;
-use reqwest::{Client, header::HeaderMap, Method};
+use reqwest::{header::HeaderMap, Method, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
 
 pub async fn request_api<T>(
-    client: &Client,
+    client: &TwitterClient,
     url: &str,
     headers: HeaderMap,
     method: Method,
@@ -13,30 +16,21 @@ pub async fn request_api<T>(
 where
     T: DeserializeOwned,
 {
-    let mut request = client
-        .request(method, url)
-        .headers(headers);
+    let response = send_with_retry(client, url, &method, &headers, &[], |headers| {
+        let mut request = client.client.request(method.clone(), url).headers(headers);
 
-    if let Some(json_body) = body {
-        request = request.json(&json_body);
-    }
+        if let Some(json_body) = &body {
+            request = request.json(json_body);
+        }
 
-    let response = request.send().await?;
-
-    if response.status().is_success() {
-        let headers = response.headers().clone();
-        let text = response.text().await?;
-        let parsed: T = serde_json::from_str(&text)?;
-        Ok((parsed, headers))
-    } else {
-        Err(crate::error::TwitterError::Api(format!(
-            "Request failed with status: {}",
-            response.status()
-        )))
-    }
+        request
+    })
+    .await?;
+
+    parse_response(response).await
 }
 
-pub async fn get_guest_token(client: &Client, bearer_token: &str) -> Result<String> {
+pub async fn get_guest_token(client: &TwitterClient, bearer_token: &str) -> Result<String> {
     let mut headers = HeaderMap::new();
     headers.insert(
         "Authorization",
@@ -45,7 +39,7 @@ pub async fn get_guest_token(client: &Client, bearer_token: &str) -> Result<Stri
 
     let (response, _) = request_api::<serde_json::Value>(
         client,
-        "https://api.twitter.com/1.1/guest/activate.json",
+        &client.urls.api_url("/1.1/guest/activate.json"),
         headers,
         Method::POST,
         None,
@@ -56,40 +50,32 @@ pub async fn get_guest_token(client: &Client, bearer_token: &str) -> Result<Stri
         .get("guest_token")
         .and_then(|token| token.as_str())
         .map(String::from)
-        .ok_or_else(|| crate::error::TwitterError::Auth("Failed to get guest token".into()))
+        .ok_or_else(|| TwitterError::Auth("Failed to get guest token".into()))
 }
 
 pub async fn request_multipart_api<T>(
-    client: &Client,
+    client: &TwitterClient,
     url: &str,
     headers: HeaderMap,
-    form: Form,
+    form: impl Fn() -> Form,
 ) -> Result<(T, HeaderMap)>
 where
     T: DeserializeOwned,
 {
-    let request = client
-        .request(Method::POST, url)
-        .headers(headers)
-        .multipart(form);
-
-    let response = request.send().await?;
-
-    if response.status().is_success() {
-        let headers = response.headers().clone();
-        let text = response.text().await?;
-        let parsed: T = serde_json::from_str(&text)?;
-        Ok((parsed, headers))
-    } else {
-        Err(crate::error::TwitterError::Api(format!(
-            "Request failed with status: {}",
-            response.status()
-        )))
-    }
+    let response = send_with_retry(client, url, &Method::POST, &headers, &[], |headers| {
+        client
+            .client
+            .request(Method::POST, url)
+            .headers(headers)
+            .multipart(form())
+    })
+    .await?;
+
+    parse_response(response).await
 }
 
 pub async fn request_form_api<T>(
-    client: &Client,
+    client: &TwitterClient,
     url: &str,
     headers: HeaderMap,
     form_data: Vec<(String, String)>,
@@ -97,22 +83,126 @@ pub async fn request_form_api<T>(
 where
     T: DeserializeOwned,
 {
-    let request = client
-        .request(Method::POST, url)
-        .headers(headers)
-        .form(&form_data);
-
-    let response = request.send().await?;
-
-    if response.status().is_success() {
-        let headers = response.headers().clone();
-        let text = response.text().await?;
-        let parsed: T = serde_json::from_str(&text)?;
-        Ok((parsed, headers))
-    } else {
-        Err(crate::error::TwitterError::Api(format!(
-            "Request failed with status: {}",
-            response.status()
-        )))
+    let response = send_with_retry(client, url, &Method::POST, &headers, &form_data, |headers| {
+        client
+            .client
+            .request(Method::POST, url)
+            .headers(headers)
+            .form(&form_data)
+    })
+    .await?;
+
+    parse_response(response).await
+}
+
+async fn send_with_retry<F>(
+    client: &TwitterClient,
+    url: &str,
+    method: &Method,
+    headers: &HeaderMap,
+    form: &[(String, String)],
+    build: F,
+) -> Result<Response>
+where
+    F: Fn(HeaderMap) -> RequestBuilder,
+{
+    let policy = &client.retry_policy;
+    let endpoint = endpoint_key(url);
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let attempts_left = attempt < policy.max_attempts;
+
+        client
+            .rate_limits
+            .acquire(&endpoint, policy.max_rate_limit_wait)
+            .await?;
+
+        // The previous attempt may have rotated cookies such as `ct0`, and
+        // signatures carry a nonce and timestamp, so each attempt gets its own.
+        let mut request_headers = headers.clone();
+        client.auth.refresh_headers(url, &mut request_headers).await?;
+        client
+            .auth
+            .authorize_request(method, url, form, &mut request_headers)
+            .await?;
+
+        let response = match build(request_headers).send().await {
+            Ok(response) => response,
+            Err(err) => {
+                if attempts_left && policy.should_retry_error(&err, method) {
+                    let delay = policy.backoff(attempt);
+                    tracing::debug!(attempt, ?delay, error = %err, "Retrying request");
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+                return Err(err.into());
+            }
+        };
+
+        client.rate_limits.record(&endpoint, response.headers());
+        client.auth.capture_cookies(url, response.headers()).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let reset_at = rate_limit_reset(response.headers());
+            let delay = match reset_at {
+                Some(reset) => (reset - Utc::now()).to_std().unwrap_or_default(),
+                None => policy.backoff(attempt),
+            };
+            if !attempts_left || delay > policy.max_rate_limit_wait {
+                return Err(TwitterError::RateLimit { reset_at });
+            }
+            tracing::debug!(attempt, ?delay, "Rate limited, waiting for reset");
+            tokio::time::sleep(delay).await;
+            continue;
+        }
+
+        if attempts_left && policy.should_retry_status(status, method) {
+            let delay = policy.backoff(attempt);
+            tracing::debug!(attempt, ?delay, %status, "Retrying request");
+            tokio::time::sleep(delay).await;
+            continue;
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        return Err(TwitterError::from_response(status.as_u16(), body));
+    }
+}
+
+async fn parse_response<T>(response: Response) -> Result<(T, HeaderMap)>
+where
+    T: DeserializeOwned,
+{
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let text = response.text().await?;
+    let value: serde_json::Value = serde_json::from_str(&text)?;
+
+    // GraphQL reports failures as `{"errors": [...]}` with HTTP 200; only a
+    // body without any `data` is a hard failure here.
+    let has_errors = value
+        .get("errors")
+        .and_then(|errors| errors.as_array())
+        .is_some_and(|errors| !errors.is_empty());
+    let has_data = value.get("data").is_some_and(|data| !data.is_null());
+    if has_errors && !has_data {
+        return Err(TwitterError::from_response(status, text));
     }
+
+    let parsed: T = serde_json::from_value(value)?;
+    Ok((parsed, headers))
+}
+
+pub(crate) fn rate_limit_reset(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    headers
+        .get("x-rate-limit-reset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i64>().ok())
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
 }
@@ -0,0 +1,125 @@
+use rand::Rng;
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// Controls how `request_api` and friends retry failed requests.
+///
+/// Non-idempotent requests (POST) are only retried when the request never
+/// reached Twitter or was rejected with 429, so a mutation is never replayed.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Longest wait for an `x-rate-limit-reset` before giving up with
+    /// `TwitterError::RateLimit` instead of sleeping.
+    pub max_rate_limit_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_rate_limit_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            max_rate_limit_wait: Duration::ZERO,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_max_rate_limit_wait(mut self, max_rate_limit_wait: Duration) -> Self {
+        self.max_rate_limit_wait = max_rate_limit_wait;
+        self
+    }
+
+    /// Exponential backoff for the given 1-based attempt, with the upper half
+    /// of the window randomised so concurrent agents don't retry in lockstep.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        let half = delay / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+
+    pub(crate) fn should_retry_status(&self, status: StatusCode, method: &Method) -> bool {
+        match status {
+            StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => is_idempotent(method),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn should_retry_error(&self, err: &reqwest::Error, method: &Method) -> bool {
+        err.is_connect() || (is_idempotent(method) && (err.is_timeout() || err.is_request()))
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_stays_in_upper_half_of_window() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(10));
+        for attempt in 1..=5 {
+            let delay = Duration::from_millis(100) * (1 << (attempt - 1));
+            for _ in 0..50 {
+                let backoff = policy.backoff(attempt);
+                assert!(backoff >= delay / 2 && backoff <= delay, "{:?}", backoff);
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_delay() {
+        let policy =
+            RetryPolicy::default().with_backoff(Duration::from_secs(1), Duration::from_secs(5));
+        for attempt in [4, 10, 100, u32::MAX] {
+            let backoff = policy.backoff(attempt);
+            assert!(backoff >= Duration::from_millis(2500) && backoff <= Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn retries_5xx_only_for_idempotent_methods() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry_status(StatusCode::SERVICE_UNAVAILABLE, &Method::GET));
+        assert!(policy.should_retry_status(StatusCode::BAD_GATEWAY, &Method::DELETE));
+        assert!(!policy.should_retry_status(StatusCode::SERVICE_UNAVAILABLE, &Method::POST));
+        assert!(!policy.should_retry_status(StatusCode::INTERNAL_SERVER_ERROR, &Method::POST));
+        assert!(!policy.should_retry_status(StatusCode::FORBIDDEN, &Method::GET));
+    }
+}
@@ -0,0 +1,59 @@
+/// Hosts used to build every request URL.
+///
+/// The defaults are the live Twitter/X hosts. Use [`BaseUrls::from_origin`] to
+/// point the whole client at a local mock server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseUrls {
+    pub api: String,
+    pub graphql: String,
+    pub upload: String,
+    pub x: String,
+    pub caps: String,
+}
+
+impl Default for BaseUrls {
+    fn default() -> Self {
+        Self {
+            api: "https://api.twitter.com".to_string(),
+            graphql: "https://twitter.com/i/api/graphql".to_string(),
+            upload: "https://upload.twitter.com".to_string(),
+            x: "https://x.com/i/api".to_string(),
+            caps: "https://caps.twitter.com".to_string(),
+        }
+    }
+}
+
+impl BaseUrls {
+    /// Routes every host through one origin such as `http://127.0.0.1:8080`,
+    /// keeping the live path prefixes so a stand-in server can match on them.
+    pub fn from_origin(origin: &str) -> Self {
+        let origin = origin.trim_end_matches('/');
+        Self {
+            api: origin.to_string(),
+            graphql: format!("{}/i/api/graphql", origin),
+            upload: origin.to_string(),
+            x: format!("{}/i/api", origin),
+            caps: origin.to_string(),
+        }
+    }
+
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.api, path)
+    }
+
+    pub fn graphql_url(&self, path: &str) -> String {
+        format!("{}{}", self.graphql, path)
+    }
+
+    pub fn upload_url(&self, path: &str) -> String {
+        format!("{}{}", self.upload, path)
+    }
+
+    pub fn x_url(&self, path: &str) -> String {
+        format!("{}{}", self.x, path)
+    }
+
+    pub fn caps_url(&self, path: &str) -> String {
+        format!("{}{}", self.caps, path)
+    }
+}
@@ -0,0 +1,895 @@
+//! Typed requests for the official v2 API.
+//!
+//! Responses come back as `{data, includes, meta}`; the expanded objects in
+//! `includes` are joined onto each result, so callers get complete
+//! [`Tweet`] and [`Profile`] values.
+
+use crate::api::client::TwitterClient;
+use crate::api::endpoints::{
+    DEFAULT_EXPANSIONS, DEFAULT_MEDIA_FIELDS, DEFAULT_PLACE_FIELDS, DEFAULT_POLL_FIELDS,
+    DEFAULT_TWEET_FIELDS, DEFAULT_USER_FIELDS,
+};
+use crate::error::{Result, TwitterError};
+use crate::models::{Mention, Photo, PlaceRaw, PollV2, Profile, Tweet, Video};
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The `*.fields` and `expansions` query parameters sent with a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    pub tweet_fields: Vec<String>,
+    pub user_fields: Vec<String>,
+    pub media_fields: Vec<String>,
+    pub poll_fields: Vec<String>,
+    pub place_fields: Vec<String>,
+    pub expansions: Vec<String>,
+}
+
+impl Default for FieldSelection {
+    fn default() -> Self {
+        Self {
+            tweet_fields: to_strings(DEFAULT_TWEET_FIELDS),
+            user_fields: to_strings(DEFAULT_USER_FIELDS),
+            media_fields: to_strings(DEFAULT_MEDIA_FIELDS),
+            poll_fields: to_strings(DEFAULT_POLL_FIELDS),
+            place_fields: to_strings(DEFAULT_PLACE_FIELDS),
+            expansions: to_strings(DEFAULT_EXPANSIONS),
+        }
+    }
+}
+
+impl FieldSelection {
+    /// Requests only the default fields of each object, with no expansions.
+    pub fn none() -> Self {
+        Self {
+            tweet_fields: Vec::new(),
+            user_fields: Vec::new(),
+            media_fields: Vec::new(),
+            poll_fields: Vec::new(),
+            place_fields: Vec::new(),
+            expansions: Vec::new(),
+        }
+    }
+
+    pub fn with_tweet_fields(mut self, fields: &[&str]) -> Self {
+        self.tweet_fields = to_strings(fields);
+        self
+    }
+
+    pub fn with_user_fields(mut self, fields: &[&str]) -> Self {
+        self.user_fields = to_strings(fields);
+        self
+    }
+
+    pub fn with_media_fields(mut self, fields: &[&str]) -> Self {
+        self.media_fields = to_strings(fields);
+        self
+    }
+
+    pub fn with_poll_fields(mut self, fields: &[&str]) -> Self {
+        self.poll_fields = to_strings(fields);
+        self
+    }
+
+    pub fn with_place_fields(mut self, fields: &[&str]) -> Self {
+        self.place_fields = to_strings(fields);
+        self
+    }
+
+    pub fn with_expansions(mut self, expansions: &[&str]) -> Self {
+        self.expansions = to_strings(expansions);
+        self
+    }
+
+    /// Keeps only the parameters that apply to user lookups, where tweets
+    /// are reached through the `pinned_tweet_id` expansion alone. `created_at`
+    /// is always requested, since every `Profile` carries it.
+    fn for_users(&self) -> Self {
+        let mut user_fields = self.user_fields.clone();
+        if !user_fields.iter().any(|field| field == "created_at") {
+            user_fields.push("created_at".to_string());
+        }
+        Self {
+            user_fields,
+            media_fields: Vec::new(),
+            poll_fields: Vec::new(),
+            place_fields: Vec::new(),
+            expansions: self
+                .expansions
+                .iter()
+                .filter(|expansion| *expansion == "pinned_tweet_id")
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    fn append_to(&self, params: &mut Vec<(String, String)>) {
+        for (name, values) in [
+            ("tweet.fields", &self.tweet_fields),
+            ("user.fields", &self.user_fields),
+            ("media.fields", &self.media_fields),
+            ("poll.fields", &self.poll_fields),
+            ("place.fields", &self.place_fields),
+            ("expansions", &self.expansions),
+        ] {
+            if !values.is_empty() {
+                params.push((name.to_string(), values.join(",")));
+            }
+        }
+    }
+}
+
+/// A hydrated v2 result with the envelope's `meta` and any partial errors,
+/// such as IDs that were not found.
+#[derive(Debug, Clone)]
+pub struct V2Response<T> {
+    pub data: T,
+    pub meta: Meta,
+    pub errors: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Meta {
+    pub result_count: Option<i64>,
+    pub next_token: Option<String>,
+    pub previous_token: Option<String>,
+    pub newest_id: Option<String>,
+    pub oldest_id: Option<String>,
+    pub total_tweet_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TweetCount {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub tweet_count: i64,
+}
+
+pub struct TweetLookup {
+    ids: Vec<String>,
+    fields: FieldSelection,
+}
+
+impl TweetLookup {
+    pub fn new<I, S>(ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            ids: ids.into_iter().map(Into::into).collect(),
+            fields: FieldSelection::default(),
+        }
+    }
+
+    pub fn with_fields(mut self, fields: FieldSelection) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub async fn send(&self, client: &TwitterClient) -> Result<V2Response<Vec<Tweet>>> {
+        let mut params = vec![("ids".to_string(), self.ids.join(","))];
+        self.fields.append_to(&mut params);
+        let envelope = get(client, "/2/tweets", params).await?;
+        envelope.into_tweets()
+    }
+}
+
+enum UserKey {
+    Ids(Vec<String>),
+    Usernames(Vec<String>),
+}
+
+pub struct UserLookup {
+    key: UserKey,
+    fields: FieldSelection,
+}
+
+impl UserLookup {
+    pub fn by_ids<I, S>(ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            key: UserKey::Ids(ids.into_iter().map(Into::into).collect()),
+            fields: FieldSelection::default(),
+        }
+    }
+
+    pub fn by_usernames<I, S>(usernames: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            key: UserKey::Usernames(usernames.into_iter().map(Into::into).collect()),
+            fields: FieldSelection::default(),
+        }
+    }
+
+    pub fn with_fields(mut self, fields: FieldSelection) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub async fn send(&self, client: &TwitterClient) -> Result<V2Response<Vec<Profile>>> {
+        let (path, mut params) = match &self.key {
+            UserKey::Ids(ids) => ("/2/users", vec![("ids".to_string(), ids.join(","))]),
+            UserKey::Usernames(usernames) => (
+                "/2/users/by",
+                vec![("usernames".to_string(), usernames.join(","))],
+            ),
+        };
+        self.fields.for_users().append_to(&mut params);
+        let envelope = get(client, path, params).await?;
+        envelope.into_users()
+    }
+}
+
+pub struct UserTimeline {
+    user_id: String,
+    max_results: Option<u32>,
+    pagination_token: Option<String>,
+    since_id: Option<String>,
+    until_id: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    exclude: Vec<String>,
+    fields: FieldSelection,
+}
+
+impl UserTimeline {
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            max_results: None,
+            pagination_token: None,
+            since_id: None,
+            until_id: None,
+            start_time: None,
+            end_time: None,
+            exclude: Vec::new(),
+            fields: FieldSelection::default(),
+        }
+    }
+
+    /// Between 5 and 100 per page.
+    pub fn with_max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    pub fn with_pagination_token(mut self, token: impl Into<String>) -> Self {
+        self.pagination_token = Some(token.into());
+        self
+    }
+
+    pub fn with_since_id(mut self, id: impl Into<String>) -> Self {
+        self.since_id = Some(id.into());
+        self
+    }
+
+    pub fn with_until_id(mut self, id: impl Into<String>) -> Self {
+        self.until_id = Some(id.into());
+        self
+    }
+
+    pub fn with_start_time(mut self, time: DateTime<Utc>) -> Self {
+        self.start_time = Some(time);
+        self
+    }
+
+    pub fn with_end_time(mut self, time: DateTime<Utc>) -> Self {
+        self.end_time = Some(time);
+        self
+    }
+
+    /// Leaves out `"retweets"` and/or `"replies"`.
+    pub fn with_exclude(mut self, exclude: &[&str]) -> Self {
+        self.exclude = to_strings(exclude);
+        self
+    }
+
+    pub fn with_fields(mut self, fields: FieldSelection) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub async fn send(&self, client: &TwitterClient) -> Result<V2Response<Vec<Tweet>>> {
+        let mut params = Vec::new();
+        push_opt(&mut params, "max_results", self.max_results);
+        push_opt(&mut params, "pagination_token", self.pagination_token.as_ref());
+        push_opt(&mut params, "since_id", self.since_id.as_ref());
+        push_opt(&mut params, "until_id", self.until_id.as_ref());
+        push_opt(&mut params, "start_time", self.start_time.map(format_time));
+        push_opt(&mut params, "end_time", self.end_time.map(format_time));
+        if !self.exclude.is_empty() {
+            params.push(("exclude".to_string(), self.exclude.join(",")));
+        }
+        self.fields.append_to(&mut params);
+
+        let path = format!("/2/users/{}/tweets", self.user_id);
+        let envelope = get(client, &path, params).await?;
+        envelope.into_tweets()
+    }
+}
+
+pub struct RecentSearch {
+    query: String,
+    max_results: Option<u32>,
+    next_token: Option<String>,
+    since_id: Option<String>,
+    until_id: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    sort_order: Option<String>,
+    fields: FieldSelection,
+}
+
+impl RecentSearch {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            max_results: None,
+            next_token: None,
+            since_id: None,
+            until_id: None,
+            start_time: None,
+            end_time: None,
+            sort_order: None,
+            fields: FieldSelection::default(),
+        }
+    }
+
+    /// Between 10 and 100 per page.
+    pub fn with_max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    pub fn with_next_token(mut self, token: impl Into<String>) -> Self {
+        self.next_token = Some(token.into());
+        self
+    }
+
+    pub fn with_since_id(mut self, id: impl Into<String>) -> Self {
+        self.since_id = Some(id.into());
+        self
+    }
+
+    pub fn with_until_id(mut self, id: impl Into<String>) -> Self {
+        self.until_id = Some(id.into());
+        self
+    }
+
+    pub fn with_start_time(mut self, time: DateTime<Utc>) -> Self {
+        self.start_time = Some(time);
+        self
+    }
+
+    pub fn with_end_time(mut self, time: DateTime<Utc>) -> Self {
+        self.end_time = Some(time);
+        self
+    }
+
+    /// `"recency"` or `"relevancy"`.
+    pub fn with_sort_order(mut self, sort_order: impl Into<String>) -> Self {
+        self.sort_order = Some(sort_order.into());
+        self
+    }
+
+    pub fn with_fields(mut self, fields: FieldSelection) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub async fn send(&self, client: &TwitterClient) -> Result<V2Response<Vec<Tweet>>> {
+        let mut params = vec![("query".to_string(), self.query.clone())];
+        push_opt(&mut params, "max_results", self.max_results);
+        push_opt(&mut params, "next_token", self.next_token.as_ref());
+        push_opt(&mut params, "since_id", self.since_id.as_ref());
+        push_opt(&mut params, "until_id", self.until_id.as_ref());
+        push_opt(&mut params, "start_time", self.start_time.map(format_time));
+        push_opt(&mut params, "end_time", self.end_time.map(format_time));
+        push_opt(&mut params, "sort_order", self.sort_order.as_ref());
+        self.fields.append_to(&mut params);
+
+        let envelope = get(client, "/2/tweets/search/recent", params).await?;
+        envelope.into_tweets()
+    }
+}
+
+pub struct TweetCounts {
+    query: String,
+    granularity: Option<String>,
+    since_id: Option<String>,
+    until_id: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+}
+
+impl TweetCounts {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            granularity: None,
+            since_id: None,
+            until_id: None,
+            start_time: None,
+            end_time: None,
+        }
+    }
+
+    /// `"minute"`, `"hour"` (the API default) or `"day"`.
+    pub fn with_granularity(mut self, granularity: impl Into<String>) -> Self {
+        self.granularity = Some(granularity.into());
+        self
+    }
+
+    pub fn with_since_id(mut self, id: impl Into<String>) -> Self {
+        self.since_id = Some(id.into());
+        self
+    }
+
+    pub fn with_until_id(mut self, id: impl Into<String>) -> Self {
+        self.until_id = Some(id.into());
+        self
+    }
+
+    pub fn with_start_time(mut self, time: DateTime<Utc>) -> Self {
+        self.start_time = Some(time);
+        self
+    }
+
+    pub fn with_end_time(mut self, time: DateTime<Utc>) -> Self {
+        self.end_time = Some(time);
+        self
+    }
+
+    pub async fn send(&self, client: &TwitterClient) -> Result<V2Response<Vec<TweetCount>>> {
+        let mut params = vec![("query".to_string(), self.query.clone())];
+        push_opt(&mut params, "granularity", self.granularity.as_ref());
+        push_opt(&mut params, "since_id", self.since_id.as_ref());
+        push_opt(&mut params, "until_id", self.until_id.as_ref());
+        push_opt(&mut params, "start_time", self.start_time.map(format_time));
+        push_opt(&mut params, "end_time", self.end_time.map(format_time));
+
+        let envelope = get(client, "/2/tweets/counts/recent", params).await?;
+        Ok(V2Response {
+            data: decode_data(envelope.data)?,
+            meta: envelope.meta,
+            errors: envelope.errors,
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    data: Value,
+    #[serde(default)]
+    includes: Includes,
+    #[serde(default)]
+    meta: Meta,
+    #[serde(default)]
+    errors: Vec<Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Includes {
+    #[serde(default)]
+    tweets: Vec<TweetV2>,
+    #[serde(default)]
+    users: Vec<UserV2>,
+    #[serde(default)]
+    media: Vec<MediaV2>,
+    #[serde(default)]
+    polls: Vec<PollV2>,
+    #[serde(default)]
+    places: Vec<PlaceV2>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TweetV2 {
+    id: String,
+    #[serde(default)]
+    text: String,
+    author_id: Option<String>,
+    conversation_id: Option<String>,
+    created_at: Option<String>,
+    possibly_sensitive: Option<bool>,
+    public_metrics: Option<TweetMetrics>,
+    entities: Option<EntitiesV2>,
+    attachments: Option<AttachmentsV2>,
+    #[serde(default)]
+    referenced_tweets: Vec<ReferencedTweet>,
+    geo: Option<GeoV2>,
+    note_tweet: Option<NoteTweetV2>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TweetMetrics {
+    retweet_count: Option<i32>,
+    reply_count: Option<i32>,
+    like_count: Option<i32>,
+    quote_count: Option<i32>,
+    bookmark_count: Option<i32>,
+    impression_count: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct EntitiesV2 {
+    #[serde(default)]
+    hashtags: Vec<HashtagV2>,
+    #[serde(default)]
+    mentions: Vec<MentionV2>,
+    #[serde(default)]
+    urls: Vec<UrlV2>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HashtagV2 {
+    tag: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MentionV2 {
+    username: String,
+    id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UrlV2 {
+    expanded_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct AttachmentsV2 {
+    #[serde(default)]
+    media_keys: Vec<String>,
+    #[serde(default)]
+    poll_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReferencedTweet {
+    #[serde(rename = "type")]
+    kind: String,
+    id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GeoV2 {
+    place_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct NoteTweetV2 {
+    text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UserV2 {
+    id: String,
+    name: String,
+    username: String,
+    created_at: Option<DateTime<Utc>>,
+    description: Option<String>,
+    location: Option<String>,
+    url: Option<String>,
+    protected: Option<bool>,
+    verified: Option<bool>,
+    profile_image_url: Option<String>,
+    pinned_tweet_id: Option<String>,
+    public_metrics: Option<UserMetrics>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UserMetrics {
+    followers_count: Option<i32>,
+    following_count: Option<i32>,
+    tweet_count: Option<i32>,
+    listed_count: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MediaV2 {
+    media_key: String,
+    #[serde(rename = "type")]
+    kind: String,
+    url: Option<String>,
+    preview_image_url: Option<String>,
+    alt_text: Option<String>,
+    #[serde(default)]
+    variants: Vec<MediaVariant>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MediaVariant {
+    bit_rate: Option<u64>,
+    content_type: String,
+    url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PlaceV2 {
+    id: String,
+    full_name: Option<String>,
+    name: Option<String>,
+    country: Option<String>,
+    country_code: Option<String>,
+    place_type: Option<String>,
+}
+
+impl Envelope {
+    /// A search with no matches has no `data` at all, so that is an empty
+    /// list here rather than an error.
+    fn into_tweets(self) -> Result<V2Response<Vec<Tweet>>> {
+        let tweets: Vec<TweetV2> = match self.data {
+            Value::Null => Vec::new(),
+            Value::Object(_) => vec![serde_json::from_value(self.data)?],
+            data => serde_json::from_value(data)?,
+        };
+        Ok(V2Response {
+            data: tweets
+                .iter()
+                .map(|tweet| hydrate_tweet(tweet, &self.includes, true))
+                .collect(),
+            meta: self.meta,
+            errors: self.errors,
+        })
+    }
+
+    fn into_users(self) -> Result<V2Response<Vec<Profile>>> {
+        let users: Vec<UserV2> = match self.data {
+            Value::Null => Vec::new(),
+            Value::Object(_) => vec![serde_json::from_value(self.data)?],
+            data => serde_json::from_value(data)?,
+        };
+        Ok(V2Response {
+            data: users.iter().map(hydrate_profile).collect::<Result<_>>()?,
+            meta: self.meta,
+            errors: self.errors,
+        })
+    }
+}
+
+/// Hydrates the `data` object of a single-tweet response such as the one
+/// from `POST /2/tweets`.
+pub(crate) fn parse_tweet_response(value: Value) -> Result<Tweet> {
+    let envelope: Envelope = serde_json::from_value(value)?;
+    let tweet: TweetV2 = decode_data(envelope.data)?;
+    Ok(hydrate_tweet(&tweet, &envelope.includes, true))
+}
+
+fn hydrate_tweet(raw: &TweetV2, includes: &Includes, follow_references: bool) -> Tweet {
+    let author = raw
+        .author_id
+        .as_ref()
+        .and_then(|id| includes.users.iter().find(|user| &user.id == id));
+    let entities = raw.entities.clone().unwrap_or_default();
+    let attachments = raw.attachments.clone().unwrap_or_default();
+    let metrics = raw.public_metrics.as_ref();
+
+    let media: Vec<&MediaV2> = attachments
+        .media_keys
+        .iter()
+        .filter_map(|key| includes.media.iter().find(|media| &media.media_key == key))
+        .collect();
+    let photos = media
+        .iter()
+        .filter(|media| media.kind == "photo")
+        .filter_map(|media| {
+            Some(Photo {
+                id: media.media_key.clone(),
+                url: media.url.clone()?,
+                alt_text: media.alt_text.clone(),
+            })
+        })
+        .collect();
+    let videos = media
+        .iter()
+        .filter(|media| media.kind == "video" || media.kind == "animated_gif")
+        .map(|media| Video {
+            id: media.media_key.clone(),
+            preview: media.preview_image_url.clone().unwrap_or_default(),
+            url: media
+                .variants
+                .iter()
+                .filter(|variant| variant.content_type == "video/mp4")
+                .max_by_key(|variant| variant.bit_rate.unwrap_or(0))
+                .map(|variant| variant.url.clone()),
+        })
+        .collect();
+
+    let referenced = |kind: &str| raw.referenced_tweets.iter().find(|r| r.kind == kind);
+    let resolve = |reference: Option<&ReferencedTweet>| {
+        reference
+            .filter(|_| follow_references)
+            .and_then(|r| includes.tweets.iter().find(|tweet| tweet.id == r.id))
+            .map(|tweet| Box::new(hydrate_tweet(tweet, includes, false)))
+    };
+    let replied_to = referenced("replied_to");
+    let quoted = referenced("quoted");
+    let retweeted = referenced("retweeted");
+
+    let time_parsed = raw
+        .created_at
+        .as_deref()
+        .and_then(|created_at| DateTime::parse_from_rfc3339(created_at).ok())
+        .map(|time| time.with_timezone(&Utc));
+    let username = author.map(|user| user.username.clone());
+
+    Tweet {
+        ext_views: None,
+        created_at: raw.created_at.clone(),
+        bookmark_count: metrics.and_then(|m| m.bookmark_count),
+        conversation_id: raw.conversation_id.clone(),
+        hashtags: entities.hashtags.iter().map(|h| h.tag.clone()).collect(),
+        html: None,
+        id: Some(raw.id.clone()),
+        in_reply_to_status: resolve(replied_to),
+        in_reply_to_status_id: replied_to.map(|r| r.id.clone()),
+        is_quoted: Some(quoted.is_some()),
+        is_pin: None,
+        is_reply: Some(replied_to.is_some()),
+        is_retweet: Some(retweeted.is_some()),
+        is_self_thread: None,
+        likes: metrics.and_then(|m| m.like_count),
+        name: author.map(|user| user.name.clone()),
+        mentions: entities
+            .mentions
+            .iter()
+            .map(|mention| {
+                let user = includes
+                    .users
+                    .iter()
+                    .find(|user| user.username.eq_ignore_ascii_case(&mention.username));
+                Mention {
+                    id: mention
+                        .id
+                        .clone()
+                        .or_else(|| user.map(|user| user.id.clone()))
+                        .unwrap_or_default(),
+                    username: Some(mention.username.clone()),
+                    name: user.map(|user| user.name.clone()),
+                }
+            })
+            .collect(),
+        permanent_url: username
+            .as_ref()
+            .map(|username| format!("https://twitter.com/{}/status/{}", username, raw.id)),
+        photos,
+        place: raw
+            .geo
+            .as_ref()
+            .and_then(|geo| geo.place_id.as_ref())
+            .and_then(|id| includes.places.iter().find(|place| &place.id == id))
+            .map(|place| PlaceRaw {
+                id: Some(place.id.clone()),
+                place_type: place.place_type.clone(),
+                name: place.name.clone(),
+                full_name: place.full_name.clone(),
+                country_code: place.country_code.clone(),
+                country: place.country.clone(),
+                bounding_box: None,
+            }),
+        quoted_status: resolve(quoted),
+        quoted_status_id: quoted.map(|r| r.id.clone()),
+        replies: metrics.and_then(|m| m.reply_count),
+        retweets: metrics.and_then(|m| m.retweet_count),
+        retweeted_status: resolve(retweeted),
+        retweeted_status_id: retweeted.map(|r| r.id.clone()),
+        text: Some(
+            raw.note_tweet
+                .as_ref()
+                .map(|note| note.text.clone())
+                .unwrap_or_else(|| raw.text.clone()),
+        ),
+        thread: Vec::new(),
+        time_parsed,
+        timestamp: time_parsed.map(|time| time.timestamp()),
+        urls: entities
+            .urls
+            .iter()
+            .filter_map(|url| url.expanded_url.clone())
+            .collect(),
+        user_id: raw.author_id.clone(),
+        username: username.clone(),
+        videos,
+        views: metrics.and_then(|m| m.impression_count),
+        sensitive_content: raw.possibly_sensitive,
+        poll: attachments
+            .poll_ids
+            .first()
+            .and_then(|id| includes.polls.iter().find(|poll| poll.id.as_ref() == Some(id)))
+            .cloned(),
+        quote_count: metrics.and_then(|m| m.quote_count),
+        reply_count: metrics.and_then(|m| m.reply_count),
+        retweet_count: metrics.and_then(|m| m.retweet_count),
+        screen_name: username,
+        thread_id: None,
+    }
+}
+
+fn hydrate_profile(raw: &UserV2) -> Result<Profile> {
+    let metrics = raw.public_metrics.as_ref();
+    let created_at = raw.created_at.ok_or_else(|| {
+        TwitterError::InvalidResponse(format!("User {} has no created_at", raw.id))
+    })?;
+    Ok(Profile {
+        id: raw.id.clone(),
+        username: raw.username.clone(),
+        name: raw.name.clone(),
+        description: raw.description.clone(),
+        location: raw.location.clone(),
+        url: raw.url.clone().filter(|url| !url.is_empty()),
+        protected: raw.protected.unwrap_or(false),
+        verified: raw.verified.unwrap_or(false),
+        followers_count: metrics.and_then(|m| m.followers_count).unwrap_or(0),
+        following_count: metrics.and_then(|m| m.following_count).unwrap_or(0),
+        tweets_count: metrics.and_then(|m| m.tweet_count).unwrap_or(0),
+        listed_count: metrics.and_then(|m| m.listed_count).unwrap_or(0),
+        created_at,
+        profile_image_url: raw
+            .profile_image_url
+            .as_ref()
+            .map(|url| url.replace("_normal", "")),
+        profile_banner_url: None,
+        pinned_tweet_id: raw.pinned_tweet_id.clone(),
+        is_blue_verified: None,
+    })
+}
+
+async fn get(client: &TwitterClient, path: &str, params: Vec<(String, String)>) -> Result<Envelope> {
+    let mut url = url::Url::parse(&client.urls.api_url(path))
+        .map_err(|e| TwitterError::Api(format!("Invalid v2 URL: {}", e)))?;
+    url.query_pairs_mut().extend_pairs(params);
+    client.request(Method::GET, url.as_str(), None).await
+}
+
+/// Decodes `data`, treating a missing value as an error: the v2 API omits it
+/// when every requested object failed.
+fn decode_data<T: DeserializeOwned>(data: Value) -> Result<T> {
+    if data.is_null() {
+        return Err(TwitterError::Api("v2 response has no data".into()));
+    }
+    Ok(serde_json::from_value(data)?)
+}
+
+fn push_opt<T: ToString>(params: &mut Vec<(String, String)>, name: &str, value: Option<T>) {
+    if let Some(value) = value {
+        params.push((name.to_string(), value.to_string()));
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_lookups_always_request_created_at() {
+        let mut params = Vec::new();
+        FieldSelection::none()
+            .with_user_fields(&["username"])
+            .for_users()
+            .append_to(&mut params);
+        assert_eq!(
+            params,
+            vec![("user.fields".to_string(), "username,created_at".to_string())]
+        );
+    }
+}
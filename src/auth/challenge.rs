@@ -0,0 +1,46 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Answer to a login challenge, turned into a `subtask_inputs` entry.
+#[derive(Debug, Clone)]
+pub enum ChallengeResponse {
+    /// Submitted as `enter_text`, the shape used by confirmation code,
+    /// identifier and two-factor prompts.
+    Text(String),
+    /// A complete `subtask_inputs` entry, for subtasks the login flow does
+    /// not know how to answer.
+    Raw(Value),
+}
+
+impl ChallengeResponse {
+    pub(crate) fn into_subtask_input(self, subtask_id: &str) -> Value {
+        match self {
+            ChallengeResponse::Text(text) => json!({
+                "subtask_id": subtask_id,
+                "enter_text": {
+                    "text": text,
+                    "link": "next_link"
+                }
+            }),
+            ChallengeResponse::Raw(input) => input,
+        }
+    }
+}
+
+/// Answers login subtasks that need outside input.
+///
+/// The login flow calls this for `LoginAcid` confirmation codes,
+/// `LoginEnterAlternateIdentifierSubtask`, `LoginTwoFactorAuthChallenge`
+/// when no TOTP secret was given, and any subtask it does not recognize.
+/// `subtask` is the subtask object from the onboarding response. Returning
+/// `None` falls back to the built-in behaviour, which is an error for
+/// prompts that cannot be answered from the login arguments.
+#[async_trait]
+pub trait LoginChallengeHandler: Send + Sync {
+    async fn handle_challenge(
+        &self,
+        subtask_id: &str,
+        subtask: &Value,
+    ) -> Result<Option<ChallengeResponse>>;
+}
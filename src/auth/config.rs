@@ -1,3 +1,6 @@
+use crate::error::Result;
+use async_trait::async_trait;
+
 pub struct AuthConfig {
     pub username: Option<String>,
     pub password: Option<String>,
@@ -28,4 +31,48 @@ impl AuthConfig {
         self.email = email;
         self
     }
-} 
\ No newline at end of file
+}
+
+/// Username/password login inputs, as passed to `Scraper::login`.
+#[derive(Debug, Clone)]
+pub struct LoginCredentials {
+    pub username: String,
+    pub password: String,
+    pub email: Option<String>,
+    pub two_factor_secret: Option<String>,
+}
+
+impl LoginCredentials {
+    pub fn new(username: String, password: String) -> Self {
+        Self {
+            username,
+            password,
+            email: None,
+            two_factor_secret: None,
+        }
+    }
+
+    pub fn with_email(mut self, email: String) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    pub fn with_two_factor_secret(mut self, secret: String) -> Self {
+        self.two_factor_secret = Some(secret);
+        self
+    }
+}
+
+/// Supplies credentials when a session has to be re-established, e.g. from a
+/// secrets manager. `LoginCredentials` provides itself.
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    async fn credentials(&self) -> Result<LoginCredentials>;
+}
+
+#[async_trait]
+impl CredentialsProvider for LoginCredentials {
+    async fn credentials(&self) -> Result<LoginCredentials> {
+        Ok(self.clone())
+    }
+}
@@ -0,0 +1,550 @@
+use crate::error::{Result, TwitterError};
+use cookie::{Cookie, Expiration, SameSite};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+use url::Url;
+
+pub const COOKIE_FILE_VERSION: u32 = 1;
+
+/// A cookie with the attributes needed to send it back to the right host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Expiry as a Unix timestamp; `None` for session cookies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
+}
+
+impl StoredCookie {
+    pub fn from_cookie(cookie: &Cookie<'_>) -> Self {
+        // Max-Age is relative to when the cookie was received, so pin it to
+        // an absolute expiry before it is written anywhere.
+        let expires = cookie
+            .expires_datetime()
+            .map(|expires| expires.unix_timestamp())
+            .or_else(|| {
+                cookie
+                    .max_age()
+                    .map(|max_age| (OffsetDateTime::now_utc() + max_age).unix_timestamp())
+            });
+
+        Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain: cookie.domain().map(String::from),
+            path: cookie.path().map(String::from),
+            expires,
+            secure: cookie.secure().unwrap_or(false),
+            http_only: cookie.http_only().unwrap_or(false),
+            same_site: cookie.same_site().map(|same_site| same_site.to_string()),
+        }
+    }
+
+    pub fn to_cookie(&self) -> Cookie<'static> {
+        let mut cookie = Cookie::new(self.name.clone(), self.value.clone());
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        if let Some(path) = &self.path {
+            cookie.set_path(path.clone());
+        }
+        if let Some(expires) = self
+            .expires
+            .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+        {
+            cookie.set_expires(Expiration::DateTime(expires));
+        }
+        cookie.set_secure(self.secure);
+        cookie.set_http_only(self.http_only);
+        cookie.set_same_site(self.same_site.as_deref().and_then(parse_same_site));
+        cookie
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Whether `host` is the cookie's domain or one of its subdomains.
+    fn matches_host(&self, host: &str) -> bool {
+        let domain = normalize_domain(self.domain.as_deref().unwrap_or_default());
+        !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        let cookie_path = self.path.as_deref().unwrap_or("/");
+        path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/')
+                || path.len() == cookie_path.len()
+                || path[cookie_path.len()..].starts_with('/'))
+    }
+}
+
+/// Session cookies keyed by domain, path and name, so a `.twitter.com` and a
+/// `.x.com` cookie with the same name are kept apart.
+#[derive(Debug, Clone, Default)]
+pub struct ScopedCookieJar {
+    cookies: BTreeMap<(String, String, String), StoredCookie>,
+}
+
+impl ScopedCookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(cookie: &StoredCookie) -> (String, String, String) {
+        (
+            normalize_domain(cookie.domain.as_deref().unwrap_or_default()),
+            cookie.path.clone().unwrap_or_else(|| "/".to_string()),
+            cookie.name.clone(),
+        )
+    }
+
+    /// Adds or replaces the cookie with the same domain, path and name.
+    /// Returns whether its value changed.
+    pub fn insert(&mut self, cookie: StoredCookie) -> bool {
+        let previous = self.cookies.insert(Self::key(&cookie), cookie.clone());
+        previous.is_none_or(|previous| previous.value != cookie.value)
+    }
+
+    /// Removes the cookie with the same domain, path and name. Returns
+    /// whether there was one.
+    pub fn remove(&mut self, cookie: &StoredCookie) -> bool {
+        self.cookies.remove(&Self::key(cookie)).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StoredCookie> {
+        self.cookies.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.iter().any(|cookie| cookie.name == name)
+    }
+
+    /// Picks one cookie per name to send to `url`, preferring the one whose
+    /// domain and path match it. twitter.com and x.com share accounts, so a
+    /// name only set on the other domain is still sent rather than dropped.
+    pub fn for_url(&self, url: &str) -> Vec<&StoredCookie> {
+        let parsed = Url::parse(url).ok();
+        let host = parsed
+            .as_ref()
+            .and_then(|url| url.host_str())
+            .map(normalize_domain)
+            .unwrap_or_default();
+        let path = parsed.as_ref().map(|url| url.path()).unwrap_or("/");
+        self.pick(|cookie| {
+            (
+                cookie.matches_host(&host),
+                cookie.matches_path(path),
+                cookie.path.as_deref().map_or(0, str::len),
+            )
+        })
+    }
+
+    /// Picks one cookie per name when there is no request host to match.
+    pub fn distinct(&self) -> Vec<&StoredCookie> {
+        self.pick(|_| ())
+    }
+
+    fn pick<K: Ord>(&self, rank: impl Fn(&StoredCookie) -> K) -> Vec<&StoredCookie> {
+        let mut picked: BTreeMap<&str, &StoredCookie> = BTreeMap::new();
+        for cookie in self.iter() {
+            match picked.get(cookie.name.as_str()) {
+                Some(current) if rank(current) >= rank(cookie) => {}
+                _ => {
+                    picked.insert(&cookie.name, cookie);
+                }
+            }
+        }
+        picked.into_values().collect()
+    }
+}
+
+fn normalize_domain(domain: &str) -> String {
+    domain.trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// On-disk cookie format written by `save_cookies_to_file`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookieFile {
+    pub version: u32,
+    pub cookies: Vec<StoredCookie>,
+}
+
+impl CookieFile {
+    pub fn from_jar(jar: &ScopedCookieJar) -> Self {
+        Self {
+            version: COOKIE_FILE_VERSION,
+            cookies: jar.iter().cloned().collect(),
+        }
+    }
+
+    /// Parses a versioned cookie file, or the older `[[name, value], ...]`
+    /// format, which is read with the defaults it used to be loaded with.
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| TwitterError::Cookie(format!("Failed to parse cookies: {}", e)))?;
+
+        if value.is_array() {
+            let pairs: Vec<(String, String)> = serde_json::from_value(value)
+                .map_err(|e| TwitterError::Cookie(format!("Failed to parse cookies: {}", e)))?;
+            let cookies = pairs
+                .into_iter()
+                .map(|(name, value)| StoredCookie {
+                    name,
+                    value,
+                    domain: Some("twitter.com".to_string()),
+                    path: Some("/".to_string()),
+                    expires: None,
+                    secure: true,
+                    http_only: true,
+                    same_site: None,
+                })
+                .collect();
+            return Ok(Self {
+                version: COOKIE_FILE_VERSION,
+                cookies,
+            });
+        }
+
+        let file: CookieFile = serde_json::from_value(value)
+            .map_err(|e| TwitterError::Cookie(format!("Failed to parse cookies: {}", e)))?;
+        if file.version > COOKIE_FILE_VERSION {
+            return Err(TwitterError::Cookie(format!(
+                "Unsupported cookie file version {}",
+                file.version
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Builds a jar from the cookies that have not expired yet.
+    pub fn into_jar(self) -> ScopedCookieJar {
+        let mut jar = ScopedCookieJar::new();
+        for stored in self
+            .cookies
+            .into_iter()
+            .filter(|cookie| !cookie.is_expired())
+        {
+            jar.insert(stored);
+        }
+        jar
+    }
+}
+
+/// Keeps cookies scoped to twitter.com or x.com, so a full browser export
+/// does not leak other sites' cookies into requests.
+pub fn is_twitter_cookie(cookie: &StoredCookie) -> bool {
+    cookie.domain.as_deref().is_some_and(|domain| {
+        let domain = domain.trim_start_matches('.');
+        ["twitter.com", "x.com"]
+            .iter()
+            .any(|host| domain == *host || domain.ends_with(&format!(".{}", host)))
+    })
+}
+
+/// Parses a Netscape `cookies.txt` file as written by curl and browser
+/// extensions.
+pub fn parse_netscape(text: &str) -> Result<Vec<StoredCookie>> {
+    let mut cookies = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 7 {
+            return Err(TwitterError::Cookie(format!(
+                "Invalid cookies.txt line {}: expected 7 tab-separated fields",
+                index + 1
+            )));
+        }
+        let expires = fields[4].parse::<i64>().map_err(|_| {
+            TwitterError::Cookie(format!("Invalid expiry on cookies.txt line {}", index + 1))
+        })?;
+
+        cookies.push(StoredCookie {
+            name: fields[5].to_string(),
+            value: fields[6..].join("\t"),
+            domain: Some(fields[0].to_string()),
+            path: Some(fields[2].to_string()),
+            expires: (expires > 0).then_some(expires),
+            secure: fields[3].eq_ignore_ascii_case("TRUE"),
+            http_only,
+            same_site: None,
+        });
+    }
+    Ok(cookies)
+}
+
+pub fn to_netscape(cookies: &[StoredCookie]) -> String {
+    let mut out = String::from("# Netscape HTTP Cookie File\n");
+    for cookie in cookies {
+        let domain = cookie.domain.as_deref().unwrap_or("x.com");
+        let include_subdomains = domain.starts_with('.');
+        out.push_str(&format!(
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            if cookie.http_only { "#HttpOnly_" } else { "" },
+            domain,
+            if include_subdomains { "TRUE" } else { "FALSE" },
+            cookie.path.as_deref().unwrap_or("/"),
+            if cookie.secure { "TRUE" } else { "FALSE" },
+            cookie.expires.unwrap_or(0),
+            cookie.name,
+            cookie.value
+        ));
+    }
+    out
+}
+
+/// One entry of the JSON array exported by EditThisCookie and Cookie-Editor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserCookie {
+    name: String,
+    value: String,
+    #[serde(default)]
+    domain: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    expiration_date: Option<f64>,
+    #[serde(default)]
+    host_only: bool,
+    #[serde(default)]
+    http_only: bool,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    session: bool,
+    #[serde(default)]
+    same_site: Option<String>,
+}
+
+pub fn parse_browser_json(json: &str) -> Result<Vec<StoredCookie>> {
+    let entries: Vec<BrowserCookie> = serde_json::from_str(json)
+        .map_err(|e| TwitterError::Cookie(format!("Failed to parse browser cookies: {}", e)))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| StoredCookie {
+            name: entry.name,
+            value: entry.value,
+            domain: entry.domain,
+            path: entry.path,
+            expires: if entry.session {
+                None
+            } else {
+                entry.expiration_date.map(|secs| secs as i64)
+            },
+            secure: entry.secure,
+            http_only: entry.http_only,
+            // Chrome exports "unspecified" when the site did not set one.
+            same_site: entry
+                .same_site
+                .filter(|same_site| parse_same_site(same_site).is_some()),
+        })
+        .collect())
+}
+
+pub fn to_browser_json(cookies: &[StoredCookie]) -> Result<String> {
+    let entries: Vec<BrowserCookie> = cookies
+        .iter()
+        .map(|cookie| BrowserCookie {
+            name: cookie.name.clone(),
+            value: cookie.value.clone(),
+            domain: cookie.domain.clone(),
+            path: cookie.path.clone(),
+            expiration_date: cookie.expires.map(|secs| secs as f64),
+            host_only: cookie
+                .domain
+                .as_deref()
+                .is_some_and(|domain| !domain.starts_with('.')),
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            session: cookie.expires.is_none(),
+            same_site: Some(
+                match cookie.same_site.as_deref().and_then(parse_same_site) {
+                    Some(SameSite::Strict) => "strict".to_string(),
+                    Some(SameSite::Lax) => "lax".to_string(),
+                    Some(SameSite::None) => "no_restriction".to_string(),
+                    None => "unspecified".to_string(),
+                },
+            ),
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&entries)
+        .map_err(|e| TwitterError::Cookie(format!("Failed to serialize cookies: {}", e)))
+}
+
+fn parse_same_site(value: &str) -> Option<SameSite> {
+    match value.to_ascii_lowercase().as_str() {
+        "strict" => Some(SameSite::Strict),
+        "lax" => Some(SameSite::Lax),
+        "none" | "no_restriction" => Some(SameSite::None),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(name: &str, value: &str, domain: &str) -> StoredCookie {
+        StoredCookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: Some(domain.to_string()),
+            path: Some("/".to_string()),
+            expires: None,
+            secure: true,
+            http_only: true,
+            same_site: None,
+        }
+    }
+
+    fn value_for<'a>(cookies: &[&'a StoredCookie], name: &str) -> Option<&'a str> {
+        cookies
+            .iter()
+            .find(|cookie| cookie.name == name)
+            .map(|cookie| cookie.value.as_str())
+    }
+
+    #[test]
+    fn keeps_same_name_on_both_domains() {
+        let mut jar = ScopedCookieJar::new();
+        jar.insert(stored("ct0", "twitter", ".twitter.com"));
+        jar.insert(stored("ct0", "x", ".x.com"));
+        assert_eq!(jar.iter().count(), 2);
+
+        let restored =
+            CookieFile::parse(&serde_json::to_string(&CookieFile::from_jar(&jar)).unwrap())
+                .unwrap()
+                .into_jar();
+        assert_eq!(restored.iter().count(), 2);
+    }
+
+    #[test]
+    fn picks_cookies_for_request_host() {
+        let mut jar = ScopedCookieJar::new();
+        jar.insert(stored("ct0", "twitter", ".twitter.com"));
+        jar.insert(stored("ct0", "x", ".x.com"));
+        jar.insert(stored("auth_token", "shared", ".x.com"));
+
+        let twitter = jar.for_url("https://api.twitter.com/1.1/account/settings.json");
+        assert_eq!(twitter.len(), 2);
+        assert_eq!(value_for(&twitter, "ct0"), Some("twitter"));
+        assert_eq!(value_for(&twitter, "auth_token"), Some("shared"));
+
+        let x = jar.for_url("https://x.com/i/api/graphql/abc/HomeTimeline");
+        assert_eq!(value_for(&x, "ct0"), Some("x"));
+    }
+
+    #[test]
+    fn replaces_by_domain_path_and_name() {
+        let mut jar = ScopedCookieJar::new();
+        assert!(jar.insert(stored("ct0", "one", ".x.com")));
+        assert!(!jar.insert(stored("ct0", "one", "x.com")));
+        assert!(jar.insert(stored("ct0", "two", ".x.com")));
+        assert_eq!(jar.iter().count(), 1);
+        assert!(jar.remove(&stored("ct0", "", ".x.com")));
+        assert!(jar.is_empty());
+    }
+
+    const NETSCAPE: &str = "# Netscape HTTP Cookie File\n\
+        #HttpOnly_.x.com\tTRUE\t/\tTRUE\t1893456000\tauth_token\tsecret\n\
+        .x.com\tTRUE\t/\tTRUE\t1893456000\tct0\tcsrf\n\
+        x.com\tFALSE\t/\tFALSE\t0\tlang\ten\n\
+        \n\
+        # a comment\n";
+
+    #[test]
+    fn parses_netscape_lines() {
+        let cookies = parse_netscape(NETSCAPE).unwrap();
+        assert_eq!(cookies.len(), 3);
+
+        let auth = &cookies[0];
+        assert_eq!(auth.name, "auth_token");
+        assert_eq!(auth.value, "secret");
+        assert_eq!(auth.domain.as_deref(), Some(".x.com"));
+        assert!(auth.http_only);
+        assert!(auth.secure);
+        assert_eq!(auth.expires, Some(1893456000));
+
+        assert!(!cookies[1].http_only);
+
+        let session = &cookies[2];
+        assert_eq!(session.domain.as_deref(), Some("x.com"));
+        assert_eq!(session.expires, None);
+        assert!(!session.secure);
+    }
+
+    #[test]
+    fn rejects_short_netscape_lines() {
+        assert!(parse_netscape(".x.com\tTRUE\t/\tTRUE\t0\tct0").is_err());
+        assert!(parse_netscape(".x.com\tTRUE\t/\tTRUE\tsoon\tct0\tcsrf").is_err());
+    }
+
+    #[test]
+    fn writes_include_subdomains_from_leading_dot() {
+        let text = to_netscape(&parse_netscape(NETSCAPE).unwrap());
+        assert!(text.contains("#HttpOnly_.x.com\tTRUE\t/\tTRUE\t1893456000\tauth_token\tsecret\n"));
+        assert!(text.contains("\nx.com\tFALSE\t/\tFALSE\t0\tlang\ten\n"));
+    }
+
+    #[test]
+    fn netscape_round_trip() {
+        let cookies = parse_netscape(NETSCAPE).unwrap();
+        assert_eq!(parse_netscape(&to_netscape(&cookies)).unwrap(), cookies);
+    }
+
+    #[test]
+    fn parses_browser_json() {
+        let json = r#"[
+            {"domain": ".x.com", "expirationDate": 1893456000.123456, "hostOnly": false,
+             "httpOnly": true, "name": "auth_token", "path": "/", "sameSite": "no_restriction",
+             "secure": true, "session": false, "storeId": "0", "value": "secret"},
+            {"domain": "x.com", "hostOnly": true, "httpOnly": false, "name": "lang",
+             "path": "/", "sameSite": "unspecified", "secure": false, "session": true,
+             "value": "en"}
+        ]"#;
+        let cookies = parse_browser_json(json).unwrap();
+
+        assert_eq!(cookies[0].expires, Some(1893456000));
+        assert_eq!(cookies[0].same_site.as_deref(), Some("no_restriction"));
+        assert!(cookies[0].http_only && cookies[0].secure);
+        assert_eq!(cookies[1].expires, None);
+        assert_eq!(cookies[1].same_site, None);
+    }
+
+    #[test]
+    fn browser_json_round_trip() {
+        let mut cookies = parse_netscape(NETSCAPE).unwrap();
+        cookies[1].same_site = Some("lax".to_string());
+        let json = to_browser_json(&cookies).unwrap();
+        assert_eq!(parse_browser_json(&json).unwrap(), cookies);
+    }
+}
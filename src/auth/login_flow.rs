@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A paused onboarding login.
+///
+/// Holds everything needed to continue the flow later, possibly in another
+/// process: the `flow_token`, the pending subtask and the session cookies
+/// and guest token collected so far. Drive it with
+/// `TwitterUserAuth::continue_login` and `TwitterUserAuth::answer_login_challenge`,
+/// and call `TwitterUserAuth::resume_login` first after deserializing it.
+///
+/// The serialized flow holds session secrets in plaintext: once the password
+/// step has passed, `cookies` includes `auth_token` and `ct0`, which are
+/// enough to act as the account. Persist it only where a session itself
+/// could be kept, e.g. encrypted with the same key as an
+/// `EncryptedFileSessionStore`, and delete it once the login completes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginFlow {
+    pub flow_token: String,
+    /// The subtask object waiting for an answer, `None` once logged in.
+    pub subtask: Option<Value>,
+    /// Session cookies in `Set-Cookie` form. Secret; see the type docs.
+    pub cookies: Vec<String>,
+    pub guest_token: Option<String>,
+}
+
+impl LoginFlow {
+    pub fn subtask_id(&self) -> Option<&str> {
+        self.subtask
+            .as_ref()
+            .and_then(|subtask| subtask.get("subtask_id"))
+            .and_then(|id| id.as_str())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.subtask.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::user_auth::TwitterUserAuth;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn resumes_from_serialized_flow() {
+        let flow = LoginFlow {
+            flow_token: "g;123:456".to_string(),
+            subtask: Some(json!({ "subtask_id": "LoginAcid" })),
+            cookies: vec![
+                "auth_token=secret; Domain=.x.com; Path=/; Secure; HttpOnly".to_string(),
+                "ct0=csrf; Domain=.x.com; Path=/; Secure".to_string(),
+            ],
+            guest_token: Some("1234".to_string()),
+        };
+        let json = serde_json::to_string(&flow).unwrap();
+        let restored: LoginFlow = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.flow_token, flow.flow_token);
+        assert_eq!(restored.subtask_id(), Some("LoginAcid"));
+        assert!(!restored.is_complete());
+
+        let auth = TwitterUserAuth::new("bearer".to_string()).await.unwrap();
+        auth.resume_login(&restored).await.unwrap();
+        assert!(auth.has_session().await);
+        let cookie_string = auth.get_cookie_string().await.unwrap();
+        assert!(cookie_string.contains("auth_token=secret"));
+        assert!(cookie_string.contains("ct0=csrf"));
+    }
+}
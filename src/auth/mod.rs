@@ -1,3 +1,8 @@
 pub mod user_auth;
 pub mod config;
-
+pub mod challenge;
+pub mod login_flow;
+pub mod cookies;
+pub mod session_store;
+pub mod oauth1;
+pub mod oauth2;
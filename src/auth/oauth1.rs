@@ -0,0 +1,277 @@
+use crate::auth::user_auth::TwitterAuth;
+use crate::error::{Result, TwitterError};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Method;
+use sha1::Sha1;
+use std::any::Any;
+
+/// OAuth 1.0a user-context auth for the official API, signing every request
+/// with HMAC-SHA1 over the method, URL and parameters.
+///
+/// JSON and multipart bodies are not part of the signature; form bodies are.
+#[derive(Clone)]
+pub struct OAuth1Auth {
+    pub consumer_key: String,
+    consumer_secret: String,
+    pub access_token: String,
+    access_token_secret: String,
+}
+
+impl OAuth1Auth {
+    pub fn new(
+        consumer_key: impl Into<String>,
+        consumer_secret: impl Into<String>,
+        access_token: impl Into<String>,
+        access_token_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            consumer_key: consumer_key.into(),
+            consumer_secret: consumer_secret.into(),
+            access_token: access_token.into(),
+            access_token_secret: access_token_secret.into(),
+        }
+    }
+
+    /// Builds the `Authorization` header value for one request, with a fresh
+    /// nonce and timestamp.
+    pub fn authorization_header(
+        &self,
+        method: &Method,
+        url: &str,
+        form: &[(String, String)],
+    ) -> Result<String> {
+        let nonce: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        self.authorization_header_with(method, url, form, &nonce, &timestamp)
+    }
+
+    fn authorization_header_with(
+        &self,
+        method: &Method,
+        url: &str,
+        form: &[(String, String)],
+        nonce: &str,
+        timestamp: &str,
+    ) -> Result<String> {
+        let mut oauth_params = vec![
+            ("oauth_consumer_key".to_string(), self.consumer_key.clone()),
+            ("oauth_nonce".to_string(), nonce.to_string()),
+            ("oauth_signature_method".to_string(), "HMAC-SHA1".to_string()),
+            ("oauth_timestamp".to_string(), timestamp.to_string()),
+            ("oauth_token".to_string(), self.access_token.clone()),
+            ("oauth_version".to_string(), "1.0".to_string()),
+        ];
+
+        let params: Vec<(String, String)> = oauth_params.iter().chain(form).cloned().collect();
+        let base_string = signature_base_string(method.as_str(), url, &params)?;
+        let signature = sign(&base_string, &self.consumer_secret, &self.access_token_secret);
+        oauth_params.push(("oauth_signature".to_string(), signature));
+        oauth_params.sort();
+
+        let fields = oauth_params
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", percent_encode(key), percent_encode(value)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(format!("OAuth {}", fields))
+    }
+}
+
+/// Encodes everything outside the RFC 3986 unreserved set, as OAuth requires.
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Builds the signature base string (RFC 5849 section 3.4.1). `params` holds
+/// the oauth_* and form parameters; query parameters are read from `url`.
+pub fn signature_base_string(method: &str, url: &str, params: &[(String, String)]) -> Result<String> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| TwitterError::Auth(format!("Invalid URL to sign: {}", e)))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| TwitterError::Auth(format!("URL to sign has no host: {}", url)))?;
+    let base_url = match parsed.port() {
+        Some(port) => format!("{}://{}:{}{}", parsed.scheme(), host, port, parsed.path()),
+        None => format!("{}://{}{}", parsed.scheme(), host, parsed.path()),
+    };
+
+    let mut encoded: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .chain(params.iter().cloned())
+        .map(|(key, value)| (percent_encode(&key), percent_encode(&value)))
+        .collect();
+    encoded.sort();
+    let normalized = encoded
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    Ok(format!(
+        "{}&{}&{}",
+        method.to_ascii_uppercase(),
+        percent_encode(&base_url),
+        percent_encode(&normalized)
+    ))
+}
+
+/// Signs a base string with HMAC-SHA1, returning the base64 signature.
+pub fn sign(base_string: &str, consumer_secret: &str, token_secret: &str) -> String {
+    let key = format!("{}&{}", percent_encode(consumer_secret), percent_encode(token_secret));
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(base_string.as_bytes());
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+#[async_trait]
+impl TwitterAuth for OAuth1Auth {
+    async fn install_headers(&self, _headers: &mut HeaderMap) -> Result<()> {
+        Ok(())
+    }
+
+    async fn authorize_request(
+        &self,
+        method: &Method,
+        url: &str,
+        form: &[(String, String)],
+        headers: &mut HeaderMap,
+    ) -> Result<()> {
+        let header = self.authorization_header(method, url, form)?;
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&header).map_err(|e| TwitterError::Auth(e.to_string()))?,
+        );
+        Ok(())
+    }
+
+    async fn get_cookies(&self) -> Result<Vec<cookie::Cookie<'_>>> {
+        Ok(Vec::new())
+    }
+
+    fn delete_token(&mut self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(params: &[(&str, &str)]) -> Vec<(String, String)> {
+        params
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    // https://developer.twitter.com/en/docs/authentication/oauth-1-0a/creating-a-signature
+    #[test]
+    fn twitter_docs_example() {
+        let params = pairs(&[
+            ("status", "Hello Ladies + Gentlemen, a signed OAuth request!"),
+            ("oauth_consumer_key", "xvz1evFS4wEEPTGEFPHBog"),
+            ("oauth_nonce", "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg"),
+            ("oauth_signature_method", "HMAC-SHA1"),
+            ("oauth_timestamp", "1318622958"),
+            ("oauth_token", "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb"),
+            ("oauth_version", "1.0"),
+        ]);
+        let base_string = signature_base_string(
+            "POST",
+            "https://api.twitter.com/1.1/statuses/update.json?include_entities=true",
+            &params,
+        )
+        .unwrap();
+        assert_eq!(
+            base_string,
+            "POST&https%3A%2F%2Fapi.twitter.com%2F1.1%2Fstatuses%2Fupdate.json&include_entities%3Dtrue%26oauth_consumer_key%3Dxvz1evFS4wEEPTGEFPHBog%26oauth_nonce%3DkYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D1318622958%26oauth_token%3D370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb%26oauth_version%3D1.0%26status%3DHello%2520Ladies%2520%252B%2520Gentlemen%252C%2520a%2520signed%2520OAuth%2520request%2521"
+        );
+        assert_eq!(
+            sign(
+                &base_string,
+                "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+                "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE"
+            ),
+            "hCtSmYh+iHYCEqBWrE7C7hYmtUk="
+        );
+    }
+
+    // RFC 5849 section 3.4.1.1.
+    #[test]
+    fn rfc5849_base_string() {
+        let params = pairs(&[
+            ("oauth_consumer_key", "9djdj82h48djs9d2"),
+            ("oauth_token", "kkk9d7dh3k39sjv7"),
+            ("oauth_signature_method", "HMAC-SHA1"),
+            ("oauth_timestamp", "137131201"),
+            ("oauth_nonce", "7d8f3e4a"),
+            ("c2", ""),
+            ("a3", "2 q"),
+        ]);
+        let base_string = signature_base_string(
+            "POST",
+            "http://example.com/request?b5=%3D%253D&a3=a&c%40=&a2=r%20b",
+            &params,
+        )
+        .unwrap();
+        assert_eq!(
+            base_string,
+            "POST&http%3A%2F%2Fexample.com%2Frequest&a2%3Dr%2520b%26a3%3D2%2520q%26a3%3Da%26b5%3D%253D%25253D%26c%2540%3D%26c2%3D%26oauth_consumer_key%3D9djdj82h48djs9d2%26oauth_nonce%3D7d8f3e4a%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D137131201%26oauth_token%3Dkkk9d7dh3k39sjv7"
+        );
+    }
+
+    // RFC 5849 section 1.2.
+    #[test]
+    fn rfc5849_signature() {
+        let auth = OAuth1Auth::new(
+            "dpf43f3p2l4k3l03",
+            "kd94hf93k423kf44",
+            "nnch734d00sl2jdk",
+            "pfkkdhi9sl3r4s00",
+        );
+        let header = auth
+            .authorization_header_with(
+                &Method::GET,
+                "http://photos.example.net/photos?file=vacation.jpg&size=original",
+                &[],
+                "kllo9940pd9333jh",
+                "1191242096",
+            )
+            .unwrap();
+        assert!(header.starts_with("OAuth "));
+        assert!(header.contains("oauth_signature=\"tR3%2BTy81lMeYAr%2FFid0kMTYa%2FWM%3D\""));
+        assert!(header.contains("oauth_token=\"nnch734d00sl2jdk\""));
+    }
+
+    #[test]
+    fn percent_encodes_reserved_characters() {
+        assert_eq!(percent_encode("Ladies + Gentlemen"), "Ladies%20%2B%20Gentlemen");
+        assert_eq!(percent_encode("An encoded string!"), "An%20encoded%20string%21");
+        assert_eq!(percent_encode("Dogs, Cats & Mice"), "Dogs%2C%20Cats%20%26%20Mice");
+        assert_eq!(percent_encode("☃"), "%E2%98%83");
+        assert_eq!(percent_encode("-._~"), "-._~");
+    }
+}
@@ -0,0 +1,461 @@
+use crate::api::client::TwitterClient;
+use crate::auth::user_auth::TwitterAuth;
+use crate::error::{Result, TwitterError};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::any::Any;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const AUTHORIZE_URL: &str = "https://twitter.com/i/oauth2/authorize";
+
+/// Refresh this long before the access token expires, so a request started
+/// just before expiry does not go out with a stale token.
+const REFRESH_MARGIN: Duration = Duration::seconds(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    TweetRead,
+    TweetWrite,
+    TweetModerateWrite,
+    UsersRead,
+    FollowsRead,
+    FollowsWrite,
+    OfflineAccess,
+    SpaceRead,
+    MuteRead,
+    MuteWrite,
+    LikeRead,
+    LikeWrite,
+    ListRead,
+    ListWrite,
+    BlockRead,
+    BlockWrite,
+    BookmarkRead,
+    BookmarkWrite,
+    MediaWrite,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TweetRead => "tweet.read",
+            Self::TweetWrite => "tweet.write",
+            Self::TweetModerateWrite => "tweet.moderate.write",
+            Self::UsersRead => "users.read",
+            Self::FollowsRead => "follows.read",
+            Self::FollowsWrite => "follows.write",
+            Self::OfflineAccess => "offline.access",
+            Self::SpaceRead => "space.read",
+            Self::MuteRead => "mute.read",
+            Self::MuteWrite => "mute.write",
+            Self::LikeRead => "like.read",
+            Self::LikeWrite => "like.write",
+            Self::ListRead => "list.read",
+            Self::ListWrite => "list.write",
+            Self::BlockRead => "block.read",
+            Self::BlockWrite => "block.write",
+            Self::BookmarkRead => "bookmark.read",
+            Self::BookmarkWrite => "bookmark.write",
+            Self::MediaWrite => "media.write",
+        }
+    }
+}
+
+/// A PKCE verifier and its S256 challenge (RFC 7636). Keep the verifier until
+/// the authorization code comes back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PkceChallenge {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl PkceChallenge {
+    pub fn new() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self::from_verifier(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub fn from_verifier(verifier: impl Into<String>) -> Self {
+        let verifier = verifier.into();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+impl Default for PkceChallenge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tokens from the OAuth 2.0 token endpoint. Serializable so the refresh
+/// token can be persisted; it is rotated on every refresh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+impl OAuth2Token {
+    pub fn needs_refresh(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - REFRESH_MARGIN <= Utc::now())
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.iter().any(|granted| granted == scope.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+impl TokenResponse {
+    fn into_token(self) -> OAuth2Token {
+        OAuth2Token {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            expires_at: self
+                .expires_in
+                .map(|secs| Utc::now() + Duration::seconds(secs)),
+            scopes: self
+                .scope
+                .map(|scope| scope.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// An OAuth 2.0 app registered in the developer portal. Public clients have
+/// no `client_secret`; confidential clients authenticate to the token
+/// endpoint with HTTP Basic.
+#[derive(Debug, Clone)]
+pub struct OAuth2App {
+    pub client_id: String,
+    client_secret: Option<String>,
+    pub redirect_uri: String,
+}
+
+impl OAuth2App {
+    pub fn new(client_id: impl Into<String>, redirect_uri: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: None,
+            redirect_uri: redirect_uri.into(),
+        }
+    }
+
+    pub fn with_client_secret(mut self, client_secret: impl Into<String>) -> Self {
+        self.client_secret = Some(client_secret.into());
+        self
+    }
+
+    /// Builds the URL to send the user to. `state` comes back unchanged on the
+    /// redirect and should be checked against the one sent.
+    pub fn authorize_url(&self, scopes: &[Scope], state: &str, pkce: &PkceChallenge) -> String {
+        let scope = scopes
+            .iter()
+            .map(Scope::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut url = url::Url::parse(AUTHORIZE_URL).expect("authorize URL is valid");
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &scope)
+            .append_pair("state", state)
+            .append_pair("code_challenge", &pkce.challenge)
+            .append_pair("code_challenge_method", "S256");
+        url.into()
+    }
+
+    pub async fn exchange_code(
+        &self,
+        client: &TwitterClient,
+        code: &str,
+        pkce: &PkceChallenge,
+    ) -> Result<OAuth2Token> {
+        self.token_request(
+            client,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.redirect_uri),
+                ("code_verifier", &pkce.verifier),
+                ("client_id", &self.client_id),
+            ],
+        )
+        .await
+    }
+
+    pub async fn refresh(
+        &self,
+        client: &TwitterClient,
+        refresh_token: &str,
+    ) -> Result<OAuth2Token> {
+        self.token_request(
+            client,
+            &[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+                ("client_id", &self.client_id),
+            ],
+        )
+        .await
+    }
+
+    async fn token_request(
+        &self,
+        client: &TwitterClient,
+        form: &[(&str, &str)],
+    ) -> Result<OAuth2Token> {
+        // Sent directly rather than through the request helpers, since the
+        // client's own auth must not sign or replace the Basic credentials.
+        let mut request = client
+            .client
+            .post(client.urls.api_url("/2/oauth2/token"))
+            .form(form);
+        if let Some(secret) = &self.client_secret {
+            request = request.basic_auth(&self.client_id, Some(secret));
+        }
+        let response: TokenResponse = send_token_request(request).await?;
+        Ok(response.into_token())
+    }
+}
+
+/// OAuth 2.0 user-context auth from the Authorization Code with PKCE flow.
+///
+/// The access token is refreshed before it expires when a refresh token was
+/// granted (the `offline.access` scope). Clones share the token.
+#[derive(Clone)]
+pub struct OAuth2UserAuth {
+    app: OAuth2App,
+    token: Arc<Mutex<OAuth2Token>>,
+}
+
+impl OAuth2UserAuth {
+    pub fn new(app: OAuth2App, token: OAuth2Token) -> Self {
+        Self {
+            app,
+            token: Arc::new(Mutex::new(token)),
+        }
+    }
+
+    /// The current token, including the latest rotated refresh token.
+    pub async fn token(&self) -> OAuth2Token {
+        self.token.lock().await.clone()
+    }
+
+    pub async fn refresh(&self, client: &TwitterClient) -> Result<()> {
+        let mut token = self.token.lock().await;
+        self.refresh_locked(client, &mut token).await
+    }
+
+    async fn refresh_locked(&self, client: &TwitterClient, token: &mut OAuth2Token) -> Result<()> {
+        let refresh_token = token
+            .refresh_token
+            .clone()
+            .ok_or_else(|| TwitterError::Auth("OAuth2 token has no refresh token".into()))?;
+        let mut refreshed = self.app.refresh(client, &refresh_token).await?;
+        if refreshed.refresh_token.is_none() {
+            refreshed.refresh_token = Some(refresh_token);
+        }
+        *token = refreshed;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TwitterAuth for OAuth2UserAuth {
+    async fn prepare(&self, client: &TwitterClient) -> Result<()> {
+        // Checked under the lock so concurrent requests refresh only once.
+        let mut token = self.token.lock().await;
+        if token.needs_refresh() && token.refresh_token.is_some() {
+            self.refresh_locked(client, &mut token).await?;
+        }
+        Ok(())
+    }
+
+    async fn install_headers(&self, headers: &mut HeaderMap) -> Result<()> {
+        let token = self.token.lock().await;
+        insert_bearer(headers, &token.access_token)
+    }
+
+    async fn get_cookies(&self) -> Result<Vec<cookie::Cookie<'_>>> {
+        Ok(Vec::new())
+    }
+
+    fn delete_token(&mut self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// App-only auth with a bearer token, for public reads on the official API.
+#[derive(Debug, Clone)]
+pub struct AppOnlyAuth {
+    bearer_token: String,
+}
+
+impl AppOnlyAuth {
+    pub fn new(bearer_token: impl Into<String>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+        }
+    }
+
+    /// Obtains a bearer token with the client credentials grant.
+    pub async fn from_client_credentials(
+        client: &TwitterClient,
+        api_key: &str,
+        api_secret: &str,
+    ) -> Result<Self> {
+        let request = client
+            .client
+            .post(client.urls.api_url("/oauth2/token"))
+            .basic_auth(api_key, Some(api_secret))
+            .form(&[("grant_type", "client_credentials")]);
+        let response: TokenResponse = send_token_request(request).await?;
+        Ok(Self::new(response.access_token))
+    }
+}
+
+#[async_trait]
+impl TwitterAuth for AppOnlyAuth {
+    async fn install_headers(&self, headers: &mut HeaderMap) -> Result<()> {
+        insert_bearer(headers, &self.bearer_token)
+    }
+
+    async fn get_cookies(&self) -> Result<Vec<cookie::Cookie<'_>>> {
+        Ok(Vec::new())
+    }
+
+    fn delete_token(&mut self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+async fn send_token_request(request: reqwest::RequestBuilder) -> Result<TokenResponse> {
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(TwitterError::Auth(format!(
+            "OAuth2 token request failed ({}): {}",
+            status, body
+        )));
+    }
+    serde_json::from_str(&body)
+        .map_err(|e| TwitterError::Auth(format!("Invalid OAuth2 token response: {}", e)))
+}
+
+fn insert_bearer(headers: &mut HeaderMap, token: &str) -> Result<()> {
+    headers.insert(
+        "Authorization",
+        HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| TwitterError::Auth(e.to_string()))?,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // RFC 7636 Appendix B.
+    #[test]
+    fn rfc7636_s256_challenge() {
+        let pkce = PkceChallenge::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn random_verifier_is_url_safe() {
+        let pkce = PkceChallenge::new();
+        assert_eq!(pkce.verifier.len(), 43);
+        assert!(pkce
+            .verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(
+            PkceChallenge::from_verifier(pkce.verifier.clone()).challenge,
+            pkce.challenge
+        );
+    }
+
+    #[test]
+    fn authorize_url_query() {
+        let app = OAuth2App::new("client-id", "https://example.com/callback");
+        let pkce = PkceChallenge::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk");
+        let url = url::Url::parse(&app.authorize_url(
+            &[Scope::TweetRead, Scope::OfflineAccess],
+            "state-123",
+            &pkce,
+        ))
+        .unwrap();
+
+        assert_eq!(url.host_str(), Some("twitter.com"));
+        assert_eq!(url.path(), "/i/oauth2/authorize");
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], "client-id");
+        assert_eq!(query["redirect_uri"], "https://example.com/callback");
+        assert_eq!(query["scope"], "tweet.read offline.access");
+        assert_eq!(query["state"], "state-123");
+        assert_eq!(
+            query["code_challenge"],
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(query["code_challenge_method"], "S256");
+    }
+
+    #[test]
+    fn token_response_scopes_and_expiry() {
+        let response: TokenResponse = serde_json::from_str(
+            r#"{"token_type":"bearer","access_token":"a","refresh_token":"r","expires_in":7200,"scope":"tweet.read users.read"}"#,
+        )
+        .unwrap();
+        let token = response.into_token();
+        assert!(token.has_scope(Scope::UsersRead));
+        assert!(!token.has_scope(Scope::TweetWrite));
+        assert!(!token.needs_refresh());
+
+        let expiring = OAuth2Token {
+            expires_at: Some(Utc::now() + Duration::seconds(30)),
+            ..token
+        };
+        assert!(expiring.needs_refresh());
+    }
+}
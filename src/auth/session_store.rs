@@ -0,0 +1,392 @@
+use crate::auth::cookies::CookieFile;
+use crate::error::{Result, TwitterError};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::Argon2;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+
+/// Persists session cookies per account.
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn load(&self, account: &str) -> Result<Option<CookieFile>>;
+    async fn save(&self, account: &str, session: &CookieFile) -> Result<()>;
+    async fn delete(&self, account: &str) -> Result<()>;
+}
+
+/// Keeps sessions in memory only; useful for tests.
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<String, CookieFile>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemorySessionStore {
+    async fn load(&self, account: &str) -> Result<Option<CookieFile>> {
+        Ok(self.sessions.lock().unwrap().get(account).cloned())
+    }
+
+    async fn save(&self, account: &str, session: &CookieFile) -> Result<()> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(account.to_string(), session.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, account: &str) -> Result<()> {
+        self.sessions.lock().unwrap().remove(account);
+        Ok(())
+    }
+}
+
+/// Writes each account's cookies as plaintext JSON to `<dir>/<account>.json`.
+#[derive(Debug, Clone)]
+pub struct FileSessionStore {
+    dir: PathBuf,
+}
+
+impl FileSessionStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl SessionStore for FileSessionStore {
+    async fn load(&self, account: &str) -> Result<Option<CookieFile>> {
+        let Some(contents) = read_session_file(&session_path(&self.dir, account, "json")).await?
+        else {
+            return Ok(None);
+        };
+        let contents = String::from_utf8(contents)
+            .map_err(|e| TwitterError::Cookie(format!("Invalid session file: {}", e)))?;
+        CookieFile::parse(&contents).map(Some)
+    }
+
+    async fn save(&self, account: &str, session: &CookieFile) -> Result<()> {
+        let json = serde_json::to_vec_pretty(session)?;
+        write_session_file(&self.dir, &session_path(&self.dir, account, "json"), &json).await
+    }
+
+    async fn delete(&self, account: &str) -> Result<()> {
+        remove_session_file(&session_path(&self.dir, account, "json")).await
+    }
+}
+
+enum SessionKey {
+    Raw([u8; 32]),
+    Passphrase(String),
+}
+
+/// Writes each account's cookies to `<dir>/<account>.session`, encrypted
+/// with AES-256-GCM.
+///
+/// The key is either 32 raw bytes (see `from_env`) or derived from a
+/// passphrase with Argon2id and a salt stored in the file. Derivation runs
+/// on the blocking pool and its result is cached per salt, so frequent saves
+/// don't re-run Argon2. The account name is bound to the ciphertext, so a
+/// file renamed to another account fails to decrypt.
+pub struct EncryptedFileSessionStore {
+    dir: PathBuf,
+    key: SessionKey,
+    derived: Mutex<Option<([u8; 16], [u8; 32])>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedSession {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedFileSessionStore {
+    pub fn with_key(dir: impl Into<PathBuf>, key: [u8; 32]) -> Self {
+        Self {
+            dir: dir.into(),
+            key: SessionKey::Raw(key),
+            derived: Mutex::new(None),
+        }
+    }
+
+    pub fn with_passphrase(dir: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            key: SessionKey::Passphrase(passphrase.into()),
+            derived: Mutex::new(None),
+        }
+    }
+
+    /// Reads a base64-encoded 32-byte key from the environment variable `var`.
+    pub fn from_env(dir: impl Into<PathBuf>, var: &str) -> Result<Self> {
+        let encoded = std::env::var(var).map_err(|_| TwitterError::EnvVar(var.to_string()))?;
+        let bytes = BASE64
+            .decode(encoded.trim())
+            .map_err(|e| TwitterError::Cookie(format!("Invalid session key in {}: {}", var, e)))?;
+        let key: [u8; 32] = bytes.try_into().map_err(|_| {
+            TwitterError::Cookie(format!("Session key in {} must be 32 bytes", var))
+        })?;
+        Ok(Self::with_key(dir, key))
+    }
+
+    async fn cipher(&self, salt: Option<&[u8]>) -> Result<Aes256Gcm> {
+        let key = match (&self.key, salt) {
+            (SessionKey::Raw(key), _) => *key,
+            (SessionKey::Passphrase(passphrase), Some(salt)) => {
+                let salt: [u8; 16] = salt
+                    .try_into()
+                    .map_err(|_| TwitterError::Cookie("Invalid session file salt".into()))?;
+                self.derive_key(passphrase, salt).await?
+            }
+            (SessionKey::Passphrase(_), None) => {
+                return Err(TwitterError::Cookie(
+                    "Session file has no salt for passphrase key".into(),
+                ))
+            }
+        };
+        Ok(Aes256Gcm::new(&key.into()))
+    }
+
+    /// The salt to save with, reusing the one whose key is already cached.
+    fn save_salt(&self) -> Option<[u8; 16]> {
+        match self.key {
+            SessionKey::Raw(_) => None,
+            SessionKey::Passphrase(_) => Some(match *self.derived.lock().unwrap() {
+                Some((salt, _)) => salt,
+                None => {
+                    let mut salt = [0u8; 16];
+                    rand::thread_rng().fill_bytes(&mut salt);
+                    salt
+                }
+            }),
+        }
+    }
+
+    async fn derive_key(&self, passphrase: &str, salt: [u8; 16]) -> Result<[u8; 32]> {
+        if let Some((cached_salt, key)) = *self.derived.lock().unwrap() {
+            if cached_salt == salt {
+                return Ok(key);
+            }
+        }
+
+        let passphrase = passphrase.to_string();
+        let key = tokio::task::spawn_blocking(move || {
+            let mut key = [0u8; 32];
+            Argon2::default()
+                .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+                .map(|_| key)
+        })
+        .await
+        .map_err(|e| TwitterError::Cookie(format!("Key derivation failed: {}", e)))?
+        .map_err(|e| TwitterError::Cookie(format!("Key derivation failed: {}", e)))?;
+
+        *self.derived.lock().unwrap() = Some((salt, key));
+        Ok(key)
+    }
+}
+
+#[async_trait]
+impl SessionStore for EncryptedFileSessionStore {
+    async fn load(&self, account: &str) -> Result<Option<CookieFile>> {
+        let path = session_path(&self.dir, account, "session");
+        let Some(contents) = read_session_file(&path).await? else {
+            return Ok(None);
+        };
+        let envelope: EncryptedSession = serde_json::from_slice(&contents)?;
+
+        let decode = |value: &str| {
+            BASE64
+                .decode(value)
+                .map_err(|e| TwitterError::Cookie(format!("Invalid session file: {}", e)))
+        };
+        let salt = envelope.salt.as_deref().map(decode).transpose()?;
+        let nonce = decode(&envelope.nonce)?;
+        let ciphertext = decode(&envelope.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err(TwitterError::Cookie("Invalid session file nonce".into()));
+        }
+
+        let plaintext = self
+            .cipher(salt.as_deref())
+            .await?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: account.as_bytes(),
+                },
+            )
+            .map_err(|_| TwitterError::Cookie("Failed to decrypt session file".into()))?;
+        let json = String::from_utf8(plaintext)
+            .map_err(|e| TwitterError::Cookie(format!("Invalid session file: {}", e)))?;
+        CookieFile::parse(&json).map(Some)
+    }
+
+    async fn save(&self, account: &str, session: &CookieFile) -> Result<()> {
+        let salt = self.save_salt();
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let plaintext = serde_json::to_vec(session)?;
+        let ciphertext = self
+            .cipher(salt.as_ref().map(|salt| salt.as_slice()))
+            .await?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: account.as_bytes(),
+                },
+            )
+            .map_err(|_| TwitterError::Cookie("Failed to encrypt session".into()))?;
+
+        let envelope = EncryptedSession {
+            version: 1,
+            salt: salt.map(|salt| BASE64.encode(salt)),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let json = serde_json::to_vec_pretty(&envelope)?;
+        let path = session_path(&self.dir, account, "session");
+        write_session_file(&self.dir, &path, &json).await
+    }
+
+    async fn delete(&self, account: &str) -> Result<()> {
+        remove_session_file(&session_path(&self.dir, account, "session")).await
+    }
+}
+
+fn session_path(dir: &Path, account: &str, extension: &str) -> PathBuf {
+    let file_name: String = account
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}.{}", file_name, extension))
+}
+
+async fn read_session_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    match tokio::fs::read(path).await {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes through a temporary file and renames it over `path`, so a crash
+/// mid-write leaves the previous session intact. Files are readable by the
+/// owner only.
+async fn write_session_file(dir: &Path, path: &Path, contents: &[u8]) -> Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{:08x}.tmp", rand::random::<u32>()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let result = async {
+        let mut file = options.open(&tmp_path).await?;
+        file.write_all(contents).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&tmp_path, path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+    }
+    Ok(result?)
+}
+
+async fn remove_session_file(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::cookies::{StoredCookie, COOKIE_FILE_VERSION};
+
+    fn session() -> CookieFile {
+        CookieFile {
+            version: COOKIE_FILE_VERSION,
+            cookies: vec![StoredCookie {
+                name: "ct0".to_string(),
+                value: "token".to_string(),
+                domain: Some(".x.com".to_string()),
+                path: Some("/".to_string()),
+                expires: None,
+                secure: true,
+                http_only: false,
+                same_site: None,
+            }],
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}-{:08x}", name, rand::random::<u32>()))
+    }
+
+    #[tokio::test]
+    async fn passphrase_round_trip() {
+        let dir = temp_dir("encrypted-session-store");
+        let store = EncryptedFileSessionStore::with_passphrase(&dir, "hunter2");
+        store.save("alice", &session()).await.unwrap();
+        store.save("alice", &session()).await.unwrap();
+
+        let reopened = EncryptedFileSessionStore::with_passphrase(&dir, "hunter2");
+        let loaded = reopened.load("alice").await.unwrap().unwrap();
+        assert_eq!(loaded.cookies, session().cookies);
+
+        let wrong = EncryptedFileSessionStore::with_passphrase(&dir, "hunter3");
+        assert!(wrong.load("alice").await.is_err());
+
+        let leftovers = std::fs::read_dir(&dir).unwrap().count();
+        assert_eq!(leftovers, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn plaintext_files_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("file-session-store");
+        let store = FileSessionStore::new(&dir);
+        store.save("alice", &session()).await.unwrap();
+
+        let mode = std::fs::metadata(dir.join("alice.json"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(store.load("alice").await.unwrap().is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,20 +1,25 @@
-use crate::api::requests::request_api;
+use crate::api::requests::{request_api, request_form_api};
+use crate::api::client::TwitterClient;
+use crate::auth::challenge::{ChallengeResponse, LoginChallengeHandler};
+use crate::auth::config::LoginCredentials;
+use crate::auth::cookies::{self, CookieFile, ScopedCookieJar, StoredCookie};
+use crate::auth::login_flow::LoginFlow;
 use crate::error::{Result, TwitterError};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use cookie::CookieJar;
 use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::any::Any;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use totp_rs::{Algorithm, TOTP};
 use tracing;
-use reqwest::Client;
 
 #[derive(Debug)]
 enum SubtaskType {
@@ -47,9 +52,38 @@ impl From<&str> for SubtaskType {
     }
 }
 
+/// Guest tokens stop working a few hours after activation; refresh a bit early.
+const GUEST_TOKEN_TTL: chrono::Duration = chrono::Duration::hours(3);
+
 #[async_trait]
 pub trait TwitterAuth: Send + Sync + Any {
+    /// Brings the session up to date before headers are installed, e.g. by
+    /// activating or refreshing a guest token.
+    async fn prepare(&self, _client: &TwitterClient) -> Result<()> {
+        Ok(())
+    }
     async fn install_headers(&self, headers: &mut HeaderMap) -> Result<()>;
+    /// Brings session-derived headers that `install_headers` put in
+    /// `headers` up to date for a request to `url`, e.g. after a response
+    /// rotated a cookie. Called before every attempt.
+    async fn refresh_headers(&self, _url: &str, _headers: &mut HeaderMap) -> Result<()> {
+        Ok(())
+    }
+    /// Adds headers that depend on the request itself, such as an OAuth
+    /// signature. Called before every attempt, with the form body if any.
+    async fn authorize_request(
+        &self,
+        _method: &Method,
+        _url: &str,
+        _form: &[(String, String)],
+        _headers: &mut HeaderMap,
+    ) -> Result<()> {
+        Ok(())
+    }
+    /// Records `Set-Cookie` headers from a response to `url`.
+    async fn capture_cookies(&self, _url: &str, _headers: &HeaderMap) -> Result<()> {
+        Ok(())
+    }
     async fn get_cookies(&self) -> Result<Vec<cookie::Cookie<'_>>>;
     fn delete_token(&mut self);
     fn as_any(&self) -> &dyn Any;
@@ -76,27 +110,66 @@ struct FlowResponse {
 #[derive(Debug, Deserialize)]
 struct Subtask {
     subtask_id: String,
+    #[serde(flatten)]
+    details: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Subtask {
+    fn to_json(&self) -> serde_json::Value {
+        let mut value = self.details.clone();
+        value.insert("subtask_id".into(), json!(self.subtask_id));
+        serde_json::Value::Object(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GuestToken {
+    token: String,
+    created_at: DateTime<Utc>,
+}
+
+impl GuestToken {
+    fn is_expired(&self) -> bool {
+        Utc::now() - self.created_at >= GUEST_TOKEN_TTL
+    }
 }
 
 #[derive(Clone)]
 pub struct TwitterUserAuth {
     bearer_token: String,
-    guest_token: Option<String>,
-    cookie_jar: Arc<Mutex<CookieJar>>,
-    created_at: Option<DateTime<Utc>>,
+    guest_token: Arc<std::sync::Mutex<Option<GuestToken>>>,
+    cookie_jar: Arc<Mutex<ScopedCookieJar>>,
+    cookies_version: Arc<AtomicU64>,
+    language: String,
+    challenge_handler: Option<Arc<dyn LoginChallengeHandler>>,
 }
 
 impl TwitterUserAuth {
     pub async fn new(bearer_token: String) -> Result<Self> {
         Ok(Self {
             bearer_token,
-            guest_token: None,
-            cookie_jar: Arc::new(Mutex::new(CookieJar::new())),
-            created_at: None,
+            guest_token: Arc::new(std::sync::Mutex::new(None)),
+            cookie_jar: Arc::new(Mutex::new(ScopedCookieJar::new())),
+            cookies_version: Arc::new(AtomicU64::new(0)),
+            language: "en".to_string(),
+            challenge_handler: None,
         })
     }
 
-    async fn init_login(&mut self, client: &Client) -> Result<FlowResponse> {
+    /// Sets the handler asked for confirmation codes, two-factor codes and
+    /// unknown subtasks during `login`.
+    pub fn with_challenge_handler(mut self, handler: Arc<dyn LoginChallengeHandler>) -> Self {
+        self.challenge_handler = Some(handler);
+        self
+    }
+
+    /// Sets the value sent in `x-twitter-client-language`.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    async fn init_login(&self, client: &TwitterClient) -> Result<FlowResponse> {
         self.update_guest_token(client).await?;
 
         let init_request = FlowInitRequest {
@@ -116,7 +189,7 @@ impl TwitterUserAuth {
 
         let (response, _) = request_api(
             client,
-            "https://api.twitter.com/1.1/onboarding/task.json",
+            &client.urls.api_url("/1.1/onboarding/task.json"),
             headers,
             reqwest::Method::POST,
             Some(json!(init_request)),
@@ -126,28 +199,19 @@ impl TwitterUserAuth {
         Ok(response)
     }
 
-    async fn execute_flow_task(&self, client: &Client, request: FlowTaskRequest) -> Result<FlowResponse> {
+    async fn execute_flow_task(&self, client: &TwitterClient, request: FlowTaskRequest) -> Result<FlowResponse> {
         let mut headers = HeaderMap::new();
         self.install_headers(&mut headers).await?;
 
-        let (flow_response, raw_response) = request_api::<FlowResponse>(
+        let (flow_response, _) = request_api::<FlowResponse>(
             client,
-            "https://api.twitter.com/1.1/onboarding/task.json",
+            &client.urls.api_url("/1.1/onboarding/task.json"),
             headers,
             reqwest::Method::POST,
             Some(json!(request)),
         )
         .await?;
 
-        let mut cookie_jar = self.cookie_jar.lock().await;
-        for cookie_header in raw_response.get_all("set-cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                if let Ok(cookie) = cookie::Cookie::parse(cookie_str) {
-                    cookie_jar.add(cookie.into_owned());
-                }
-            }
-        }
-
         if let Some(subtasks) = &flow_response.subtasks {
             if subtasks.iter().any(|s| s.subtask_id == "DenyLoginSubtask") {
                 return Err(TwitterError::Auth("Login denied".into()));
@@ -159,80 +223,233 @@ impl TwitterUserAuth {
 
     pub async fn login(
         &mut self,
-        client: &Client,
+        client: &TwitterClient,
         username: &str,
         password: &str,
         email: Option<&str>,
         two_factor_secret: Option<&str>,
     ) -> Result<()> {
-        let mut flow_response = self.init_login(client).await?;
-        let mut flow_token = flow_response.flow_token;
-
-        while let Some(subtasks) = &flow_response.subtasks {
-            if let Some(subtask) = subtasks.first() {
-                flow_response = match SubtaskType::from(subtask.subtask_id.as_str()) {
-                    SubtaskType::LoginJsInstrumentation => {
-                        self.handle_js_instrumentation_subtask(client, flow_token).await?
-                    }
-                    SubtaskType::LoginEnterUserIdentifier => {
-                        self.handle_username_input(client, flow_token, username).await?
-                    }
-                    SubtaskType::LoginEnterPassword => {
-                        self.handle_password_input(client, flow_token, password).await?
-                    }
-                    SubtaskType::LoginAcid => {
-                        if let Some(email_str) = email {
-                            self.handle_email_verification(client, flow_token, email_str)
-                                .await?
-                        } else {
-                            return Err(TwitterError::Auth(
-                                "Email required for verification".into(),
-                            ));
-                        }
-                    }
-                    SubtaskType::AccountDuplicationCheck => {
-                        self.handle_account_duplication_check(client, flow_token).await?
-                    }
-                    SubtaskType::LoginTwoFactorAuthChallenge => {
-                        if let Some(secret) = two_factor_secret {
-                            self.handle_two_factor_auth(client, flow_token, secret).await?
-                        } else {
-                            return Err(TwitterError::Auth(
-                                "Two factor authentication required".into(),
-                            ));
-                        }
-                    }
-                    SubtaskType::LoginEnterAlternateIdentifier => {
-                        if let Some(email_str) = email {
-                            self.handle_alternate_identifier(client, flow_token, email_str)
-                                .await?
-                        } else {
-                            return Err(TwitterError::Auth(
-                                "Email required for alternate identifier".into(),
-                            ));
-                        }
-                    }
-                    SubtaskType::LoginSuccess => self.handle_success_subtask(client, flow_token).await?,
-                    SubtaskType::DenyLogin => {
-                        return Err(TwitterError::Auth("Login denied".into()));
-                    }
-                    SubtaskType::Unknown(id) => {
-                        return Err(TwitterError::Auth(format!(
-                            "Unhandled subtask: {}",
-                            id
-                        )));
-                    }
-                };
-                flow_token = flow_response.flow_token;
-            } else {
-                break;
+        let credentials = LoginCredentials {
+            username: username.to_string(),
+            password: password.to_string(),
+            email: email.map(String::from),
+            two_factor_secret: two_factor_secret.map(String::from),
+        };
+
+        let mut flow = self.begin_login(client).await?;
+        self.continue_login(client, &mut flow, &credentials).await?;
+
+        let Some(subtask_id) = flow.subtask_id() else {
+            return Ok(());
+        };
+        let message = match SubtaskType::from(subtask_id) {
+            SubtaskType::LoginAcid => "Email required for verification".to_string(),
+            SubtaskType::LoginTwoFactorAuthChallenge => {
+                "Two factor authentication required".to_string()
+            }
+            SubtaskType::LoginEnterAlternateIdentifier => {
+                "Email required for alternate identifier".to_string()
             }
+            _ => format!("Unhandled subtask: {}", subtask_id),
+        };
+        Err(TwitterError::Auth(message))
+    }
+
+    /// Starts the onboarding flow and returns it paused at its first subtask.
+    pub async fn begin_login(&self, client: &TwitterClient) -> Result<LoginFlow> {
+        let response = self.init_login(client).await?;
+        let mut flow = LoginFlow {
+            flow_token: String::new(),
+            subtask: None,
+            cookies: Vec::new(),
+            guest_token: None,
+        };
+        self.advance_login_flow(&mut flow, response).await;
+        Ok(flow)
+    }
+
+    /// Restores the cookies and guest token saved in `flow`, e.g. after it
+    /// was deserialized in another process.
+    pub async fn resume_login(&self, flow: &LoginFlow) -> Result<()> {
+        let mut cookie_jar = self.cookie_jar.lock().await;
+        *cookie_jar = ScopedCookieJar::new();
+        for cookie_str in &flow.cookies {
+            let cookie = cookie::Cookie::parse(cookie_str.as_str())
+                .map_err(|e| TwitterError::Cookie(format!("Invalid login flow cookie: {}", e)))?;
+            cookie_jar.insert(StoredCookie::from_cookie(&cookie));
+        }
+
+        *self.guest_token.lock().unwrap() = flow.guest_token.clone().map(|token| GuestToken {
+            token,
+            created_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Answers every subtask it can from `credentials` and the challenge
+    /// handler. Stops once the flow completes or reaches a subtask that needs
+    /// outside input, which is left in `flow.subtask`.
+    pub async fn continue_login(
+        &self,
+        client: &TwitterClient,
+        flow: &mut LoginFlow,
+        credentials: &LoginCredentials,
+    ) -> Result<()> {
+        while let Some(subtask) = flow.subtask.clone() {
+            let Some(response) = self
+                .answer_subtask(client, &flow.flow_token, &subtask, credentials)
+                .await?
+            else {
+                break;
+            };
+            self.advance_login_flow(flow, response).await;
         }
+        Ok(())
+    }
 
+    /// Submits `response` as the answer to the pending subtask of `flow`.
+    pub async fn answer_login_challenge(
+        &self,
+        client: &TwitterClient,
+        flow: &mut LoginFlow,
+        response: ChallengeResponse,
+    ) -> Result<()> {
+        let subtask_id = flow
+            .subtask_id()
+            .ok_or_else(|| TwitterError::Auth("Login flow has no pending subtask".into()))?
+            .to_string();
+
+        let request = FlowTaskRequest {
+            flow_token: flow.flow_token.clone(),
+            subtask_inputs: vec![response.into_subtask_input(&subtask_id)],
+        };
+        let flow_response = self.execute_flow_task(client, request).await?;
+        self.advance_login_flow(flow, flow_response).await;
         Ok(())
     }
 
-    async fn handle_js_instrumentation_subtask(&self, client: &Client, flow_token: String) -> Result<FlowResponse> {
+    async fn advance_login_flow(&self, flow: &mut LoginFlow, response: FlowResponse) {
+        flow.flow_token = response.flow_token;
+        flow.subtask = response
+            .subtasks
+            .as_ref()
+            .and_then(|subtasks| subtasks.first())
+            .map(Subtask::to_json);
+        flow.cookies = self
+            .cookie_jar
+            .lock()
+            .await
+            .iter()
+            .map(|cookie| cookie.to_cookie().to_string())
+            .collect();
+        flow.guest_token = self
+            .guest_token
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|guest| guest.token.clone());
+    }
+
+    /// Answers one subtask from the credentials or the challenge handler.
+    /// Returns `None` when it needs input neither of them can provide.
+    async fn answer_subtask(
+        &self,
+        client: &TwitterClient,
+        flow_token: &str,
+        subtask: &serde_json::Value,
+        credentials: &LoginCredentials,
+    ) -> Result<Option<FlowResponse>> {
+        let subtask_id = subtask
+            .get("subtask_id")
+            .and_then(|id| id.as_str())
+            .unwrap_or_default();
+        let flow_token = flow_token.to_string();
+
+        let response = match SubtaskType::from(subtask_id) {
+            SubtaskType::LoginJsInstrumentation => {
+                self.handle_js_instrumentation_subtask(client, flow_token).await?
+            }
+            SubtaskType::LoginEnterUserIdentifier => {
+                self.handle_username_input(client, flow_token, &credentials.username)
+                    .await?
+            }
+            SubtaskType::LoginEnterPassword => {
+                self.handle_password_input(client, flow_token, &credentials.password)
+                    .await?
+            }
+            SubtaskType::LoginAcid => {
+                if let Some(response) = self
+                    .answer_challenge(client, &flow_token, subtask_id, subtask)
+                    .await?
+                {
+                    response
+                } else if let Some(email) = &credentials.email {
+                    self.handle_email_verification(client, flow_token, email)
+                        .await?
+                } else {
+                    return Ok(None);
+                }
+            }
+            SubtaskType::AccountDuplicationCheck => {
+                self.handle_account_duplication_check(client, flow_token).await?
+            }
+            SubtaskType::LoginTwoFactorAuthChallenge => {
+                if let Some(secret) = &credentials.two_factor_secret {
+                    self.handle_two_factor_auth(client, flow_token, secret).await?
+                } else {
+                    return self
+                        .answer_challenge(client, &flow_token, subtask_id, subtask)
+                        .await;
+                }
+            }
+            SubtaskType::LoginEnterAlternateIdentifier => {
+                if let Some(response) = self
+                    .answer_challenge(client, &flow_token, subtask_id, subtask)
+                    .await?
+                {
+                    response
+                } else if let Some(email) = &credentials.email {
+                    self.handle_alternate_identifier(client, flow_token, email)
+                        .await?
+                } else {
+                    return Ok(None);
+                }
+            }
+            SubtaskType::LoginSuccess => self.handle_success_subtask(client, flow_token).await?,
+            SubtaskType::DenyLogin => {
+                return Err(TwitterError::Auth("Login denied".into()));
+            }
+            SubtaskType::Unknown(id) => {
+                return self.answer_challenge(client, &flow_token, &id, subtask).await;
+            }
+        };
+        Ok(Some(response))
+    }
+
+    /// Asks the challenge handler, if any, for the subtask input and submits
+    /// it. Returns `None` when there is no handler or it declined.
+    async fn answer_challenge(
+        &self,
+        client: &TwitterClient,
+        flow_token: &str,
+        subtask_id: &str,
+        subtask: &serde_json::Value,
+    ) -> Result<Option<FlowResponse>> {
+        let Some(handler) = &self.challenge_handler else {
+            return Ok(None);
+        };
+        let Some(response) = handler.handle_challenge(subtask_id, subtask).await? else {
+            return Ok(None);
+        };
+
+        let request = FlowTaskRequest {
+            flow_token: flow_token.to_string(),
+            subtask_inputs: vec![response.into_subtask_input(subtask_id)],
+        };
+        self.execute_flow_task(client, request).await.map(Some)
+    }
+
+    async fn handle_js_instrumentation_subtask(&self, client: &TwitterClient, flow_token: String) -> Result<FlowResponse> {
         let request = FlowTaskRequest {
             flow_token,
             subtask_inputs: vec![json!({
@@ -248,7 +465,7 @@ impl TwitterUserAuth {
 
     async fn handle_username_input(
         &self,
-        client: &Client,
+        client: &TwitterClient,
         flow_token: String,
         username: &str,
     ) -> Result<FlowResponse> {
@@ -276,7 +493,7 @@ impl TwitterUserAuth {
 
     async fn handle_password_input(
         &self,
-        client: &Client,
+        client: &TwitterClient,
         flow_token: String,
         password: &str,
     ) -> Result<FlowResponse> {
@@ -295,7 +512,7 @@ impl TwitterUserAuth {
 
     async fn handle_email_verification(
         &self,
-        client: &Client,
+        client: &TwitterClient,
         flow_token: String,
         email: &str,
     ) -> Result<FlowResponse> {
@@ -312,7 +529,7 @@ impl TwitterUserAuth {
         self.execute_flow_task(client, request).await
     }
 
-    async fn handle_account_duplication_check(&self, client: &Client, flow_token: String) -> Result<FlowResponse> {
+    async fn handle_account_duplication_check(&self, client: &TwitterClient, flow_token: String) -> Result<FlowResponse> {
         let request = FlowTaskRequest {
             flow_token,
             subtask_inputs: vec![json!({
@@ -327,7 +544,7 @@ impl TwitterUserAuth {
 
     async fn handle_two_factor_auth(
         &self,
-        client: &Client,
+        client: &TwitterClient,
         flow_token: String,
         secret: &str,
     ) -> Result<FlowResponse> {
@@ -353,7 +570,7 @@ impl TwitterUserAuth {
 
     async fn handle_alternate_identifier(
         &self,
-        client: &Client,
+        client: &TwitterClient,
         flow_token: String,
         email: &str,
     ) -> Result<FlowResponse> {
@@ -370,7 +587,7 @@ impl TwitterUserAuth {
         self.execute_flow_task(client, request).await
     }
 
-    async fn handle_success_subtask(&self, client: &Client, flow_token: String) -> Result<FlowResponse> {
+    async fn handle_success_subtask(&self, client: &TwitterClient, flow_token: String) -> Result<FlowResponse> {
         let request = FlowTaskRequest {
             flow_token,
             subtask_inputs: vec![],
@@ -378,8 +595,27 @@ impl TwitterUserAuth {
         self.execute_flow_task(client, request).await
     }
 
-    async fn update_guest_token(&mut self, client: &Client) -> Result<()> {
-        let url = "https://api.twitter.com/1.1/guest/activate.json";
+    /// Whether the jar holds a logged-in session (`auth_token` cookie).
+    pub async fn has_session(&self) -> bool {
+        self.cookie_jar.lock().await.contains("auth_token")
+    }
+
+    /// Activates a guest token when there is none or the current one expired.
+    pub async fn ensure_guest_token(&self, client: &TwitterClient) -> Result<()> {
+        let needs_refresh = self
+            .guest_token
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_none_or(|guest| guest.is_expired());
+        if needs_refresh {
+            self.update_guest_token(client).await?;
+        }
+        Ok(())
+    }
+
+    async fn update_guest_token(&self, client: &TwitterClient) -> Result<()> {
+        let url = client.urls.api_url("/1.1/guest/activate.json");
 
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -389,46 +625,82 @@ impl TwitterUserAuth {
         );
 
         let (response, _) =
-            request_api::<serde_json::Value>(client, url, headers, reqwest::Method::POST, None).await?;
+            request_api::<serde_json::Value>(client, &url, headers, reqwest::Method::POST, None)
+                .await?;
 
         let guest_token = response
             .get("guest_token")
             .and_then(|token| token.as_str())
             .ok_or_else(|| TwitterError::Auth("Failed to get guest token".into()))?;
 
-        self.guest_token = Some(guest_token.to_string());
-        self.created_at = Some(Utc::now());
+        *self.guest_token.lock().unwrap() = Some(GuestToken {
+            token: guest_token.to_string(),
+            created_at: Utc::now(),
+        });
 
         Ok(())
     }
 
     pub async fn update_cookies(&self, response: &reqwest::Response) -> Result<()> {
-        tracing::trace!("Updating cookies - attempting to lock");
+        self.update_cookies_from_headers(response.url().as_str(), response.headers())
+            .await;
+        Ok(())
+    }
+
+    /// Applies `Set-Cookie` headers from a response to `url` to the jar,
+    /// removing cookies the server expired. Cookies without a `Domain` are
+    /// scoped to the responding host. Returns whether anything changed.
+    pub async fn update_cookies_from_headers(&self, url: &str, headers: &HeaderMap) -> bool {
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from));
+        let mut changed = false;
         let mut cookie_jar = self.cookie_jar.lock().await;
 
-        for cookie_header in response.headers().get_all("set-cookie") {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                if let Ok(cookie) = cookie::Cookie::parse(cookie_str) {
-                    tracing::trace!(?cookie, "Adding cookie");
-                    cookie_jar.add(cookie.into_owned());
-                }
+        for cookie_header in headers.get_all("set-cookie") {
+            let Some(cookie) = cookie_header
+                .to_str()
+                .ok()
+                .and_then(|cookie_str| cookie::Cookie::parse(cookie_str.to_string()).ok())
+            else {
+                continue;
+            };
+            tracing::trace!(?cookie, "Updating cookie");
+
+            let mut stored = StoredCookie::from_cookie(&cookie);
+            if stored.domain.is_none() {
+                stored.domain = host.clone();
+            }
+            if stored.path.is_none() {
+                stored.path = Some("/".to_string());
+            }
+
+            if stored.is_expired() {
+                changed |= cookie_jar.remove(&stored);
+            } else {
+                // Also refreshes attributes such as expiry when the value is
+                // unchanged.
+                changed |= cookie_jar.insert(stored);
             }
         }
 
-        Ok(())
+        if changed {
+            self.cookies_version.fetch_add(1, Ordering::SeqCst);
+        }
+        changed
+    }
+
+    /// Counter bumped whenever a response changed a cookie, so callers can
+    /// tell when the session needs saving again.
+    pub fn cookies_version(&self) -> u64 {
+        self.cookies_version.load(Ordering::SeqCst)
     }
 
     pub async fn save_cookies_to_file(&self, file_path: &str) -> Result<()> {
         tracing::trace!("Saving cookies - attempting to lock");
-        let cookie_jar = self.cookie_jar.lock().await;
-        let cookies: Vec<_> = cookie_jar.iter().collect();
+        let cookie_file = CookieFile::from_jar(&*self.cookie_jar.lock().await);
 
-        let cookie_data: Vec<(String, String)> = cookies
-            .iter()
-            .map(|cookie| (cookie.name().to_string(), cookie.value().to_string()))
-            .collect();
-
-        let json = serde_json::to_string_pretty(&cookie_data)
+        let json = serde_json::to_string_pretty(&cookie_file)
             .map_err(|e| TwitterError::Cookie(format!("Failed to serialize cookies: {}", e)))?;
 
         let mut file = OpenOptions::new()
@@ -443,7 +715,6 @@ impl TwitterUserAuth {
 
         Ok(())
     }
-
     pub async fn load_cookies_from_file(&mut self, file_path: &str) -> Result<()> {
         tracing::trace!("Loading cookies - attempting to lock");
 
@@ -456,83 +727,55 @@ impl TwitterUserAuth {
         let mut contents = String::new();
         file.read_to_string(&mut contents)
             .map_err(|e| TwitterError::Cookie(format!("Failed to read cookie file: {}", e)))?;
-        let cookie_data: Vec<(String, String)> = serde_json::from_str(&contents)
-            .map_err(|e| TwitterError::Cookie(format!("Failed to parse cookie file: {}", e)))?;
+        let cookie_file = CookieFile::parse(&contents)?;
 
-        tracing::trace!(?cookie_data, "Loaded cookie data");
+        tracing::trace!(?cookie_file, "Loaded cookie data");
 
-        let mut cookie_jar = self.cookie_jar.lock().await;
-
-        *cookie_jar = CookieJar::new();
-
-        for (name, value) in cookie_data {
-            let cookie = cookie::Cookie::build(name, value)
-                .path("/")
-                .domain("twitter.com")
-                .secure(true)
-                .http_only(true)
-                .finish();
-            cookie_jar.add(cookie.into_owned());
-        }
-        let mut headers = HeaderMap::new();
-        self.install_headers(&mut headers).await?;
+        *self.cookie_jar.lock().await = cookie_file.into_jar();
         Ok(())
     }
 
     pub async fn get_cookie_string(&self) -> Result<String> {
         let cookie_jar = self.cookie_jar.lock().await;
-        let cookies: Vec<_> = cookie_jar.iter().collect();
 
-        let cookie_string = cookies
+        let cookie_string = cookie_jar
+            .distinct()
             .iter()
-            .map(|c| format!("{}={}", c.name(), c.value()))
+            .map(|c| format!("{}={}", c.name, c.value))
             .collect::<Vec<_>>()
             .join("; ");
 
         Ok(cookie_string)
     }
 
+    /// Replaces the jar with cookies in the cookie file format, versioned or
+    /// legacy `[[name, value], ...]` pairs. Expired cookies are dropped.
     pub async fn set_cookies(&mut self, json_str: &str) -> Result<()> {
-        let cookie_data: Vec<(String, String)> = serde_json::from_str(json_str)
-            .map_err(|e| TwitterError::Cookie(format!("Failed to parse cookie JSON: {}", e)))?;
-
-        let mut cookie_jar = self.cookie_jar.lock().await;
-
-        *cookie_jar = CookieJar::new();
-
-        for (name, value) in cookie_data {
-            let cookie = cookie::Cookie::build(name, value)
-                .path("/")
-                .domain("twitter.com")
-                .secure(true)
-                .http_only(true)
-                .finish();
-            cookie_jar.add(cookie.into_owned());
-        }
-
-        let mut headers = HeaderMap::new();
-        self.install_headers(&mut headers).await?;
+        let cookie_file = CookieFile::parse(json_str)?;
+        *self.cookie_jar.lock().await = cookie_file.into_jar();
         Ok(())
     }
 
     pub async fn set_from_cookie_string(&mut self, cookie_string: &str) -> Result<()> {
         let mut cookie_jar = self.cookie_jar.lock().await;
-        *cookie_jar = CookieJar::new();
+        *cookie_jar = ScopedCookieJar::new();
         for cookie_str in cookie_string.split(';') {
             let cookie_str = cookie_str.trim();
             if let Ok(cookie) = cookie::Cookie::parse(cookie_str) {
-                let cookie =
-                    cookie::Cookie::build(cookie.name().to_string(), cookie.value().to_string())
-                        .path("/")
-                        .domain("twitter.com")
-                        .secure(true)
-                        .http_only(true)
-                        .finish();
-                cookie_jar.add(cookie.into_owned());
+                cookie_jar.insert(StoredCookie {
+                    name: cookie.name().to_string(),
+                    value: cookie.value().to_string(),
+                    domain: Some("twitter.com".to_string()),
+                    path: Some("/".to_string()),
+                    expires: None,
+                    secure: true,
+                    http_only: true,
+                    same_site: None,
+                });
             }
         }
-        let has_essential_cookies = cookie_jar.iter().any(|c| c.name() == "ct0")
-            && cookie_jar.iter().any(|c| c.name() == "auth_token");
+        let has_essential_cookies =
+            cookie_jar.contains("ct0") && cookie_jar.contains("auth_token");
 
         if !has_essential_cookies {
             return Err(TwitterError::Cookie(
@@ -542,13 +785,136 @@ impl TwitterUserAuth {
         Ok(())
     }
 
-    pub async fn is_logged_in(&self, client: &Client) -> Result<bool> {
+    /// Ends the session on the server, then drops all cookies and the guest
+    /// token. Local state is cleared even if the server call fails.
+    pub async fn logout(&self, client: &TwitterClient) -> Result<()> {
+        let result = if self.has_session().await {
+            let mut headers = HeaderMap::new();
+            self.install_headers(&mut headers).await?;
+            request_form_api::<serde_json::Value>(
+                client,
+                &client.urls.api_url("/1.1/account/logout.json"),
+                headers,
+                vec![(
+                    "redirectAfterLogout".to_string(),
+                    "https://x.com/account/switch".to_string(),
+                )],
+            )
+            .await
+            .map(|_| ())
+        } else {
+            Ok(())
+        };
+
+        self.clear_session().await;
+        result
+    }
+
+    /// Drops all cookies and the guest token.
+    pub async fn clear_session(&self) {
+        *self.cookie_jar.lock().await = ScopedCookieJar::new();
+        *self.guest_token.lock().unwrap() = None;
+        self.cookies_version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Copies the current cookies for persisting elsewhere.
+    pub async fn session_snapshot(&self) -> CookieFile {
+        CookieFile::from_jar(&*self.cookie_jar.lock().await)
+    }
+
+    /// Replaces the jar with a previously saved session.
+    pub async fn restore_session(&self, session: CookieFile) {
+        *self.cookie_jar.lock().await = session.into_jar();
+    }
+
+    /// Replaces the jar with twitter.com and x.com cookies from a Netscape
+    /// `cookies.txt` export.
+    pub async fn import_netscape_cookies(&mut self, text: &str) -> Result<()> {
+        self.import_cookies(cookies::parse_netscape(text)?).await
+    }
+
+    pub async fn export_netscape_cookies(&self) -> Result<String> {
+        Ok(cookies::to_netscape(&self.stored_cookies().await))
+    }
+
+    /// Replaces the jar with twitter.com and x.com cookies from an
+    /// EditThisCookie or Cookie-Editor JSON export.
+    pub async fn import_browser_cookies(&mut self, json: &str) -> Result<()> {
+        self.import_cookies(cookies::parse_browser_json(json)?).await
+    }
+
+    pub async fn export_browser_cookies(&self) -> Result<String> {
+        cookies::to_browser_json(&self.stored_cookies().await)
+    }
+
+    async fn stored_cookies(&self) -> Vec<StoredCookie> {
+        let cookie_jar = self.cookie_jar.lock().await;
+        cookie_jar.iter().cloned().collect()
+    }
+
+    async fn import_cookies(&mut self, imported: Vec<StoredCookie>) -> Result<()> {
+        let cookie_file = CookieFile {
+            version: cookies::COOKIE_FILE_VERSION,
+            cookies: imported
+                .into_iter()
+                .filter(|cookie| cookies::is_twitter_cookie(cookie) && !cookie.is_expired())
+                .collect(),
+        };
+
+        let has_cookie = |name: &str| cookie_file.cookies.iter().any(|c| c.name == name);
+        if !has_cookie("ct0") || !has_cookie("auth_token") {
+            return Err(TwitterError::Cookie(
+                "Missing essential cookies (ct0 or auth_token)".into(),
+            ));
+        }
+
+        *self.cookie_jar.lock().await = cookie_file.into_jar();
+        Ok(())
+    }
+
+    /// Sets `Cookie` and the matching `x-csrf-token` from the jar, choosing
+    /// the cookies scoped to `url` when it is known.
+    async fn install_cookie_headers(
+        &self,
+        url: Option<&str>,
+        headers: &mut HeaderMap,
+    ) -> Result<()> {
+        let cookie_jar = self.cookie_jar.lock().await;
+        let cookies = match url {
+            Some(url) => cookie_jar.for_url(url),
+            None => cookie_jar.distinct(),
+        };
+        if cookies.is_empty() {
+            return Ok(());
+        }
+
+        let cookie_header = cookies
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        headers.insert(
+            "Cookie",
+            HeaderValue::from_str(&cookie_header).map_err(|e| TwitterError::Auth(e.to_string()))?,
+        );
+
+        if let Some(csrf_cookie) = cookies.iter().find(|c| c.name == "ct0") {
+            headers.insert(
+                "x-csrf-token",
+                HeaderValue::from_str(&csrf_cookie.value)
+                    .map_err(|e| TwitterError::Auth(e.to_string()))?,
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn is_logged_in(&self, client: &TwitterClient) -> Result<bool> {
         let mut headers = HeaderMap::new();
         self.install_headers(&mut headers).await?;
 
         let (response, _) = request_api::<serde_json::Value>(
             client,
-            "https://api.twitter.com/1.1/account/verify_credentials.json",
+            &client.urls.api_url("/1.1/account/verify_credentials.json"),
             headers,
             reqwest::Method::GET,
             None,
@@ -573,43 +939,33 @@ impl TwitterUserAuth {
 
 #[async_trait]
 impl TwitterAuth for TwitterUserAuth {
-    async fn install_headers(&self, headers: &mut HeaderMap) -> Result<()> {
-        let cookie_jar = self.cookie_jar.lock().await;
-        let cookies: Vec<_> = cookie_jar.iter().collect();
-        if !cookies.is_empty() {
-            let cookie_header = cookies
-                .iter()
-                .map(|c| format!("{}={}", c.name(), c.value()))
-                .collect::<Vec<_>>()
-                .join("; ");
-
-            headers.insert(
-                "Cookie",
-                HeaderValue::from_str(&cookie_header)
-                    .map_err(|e| TwitterError::Auth(e.to_string()))?,
-            );
-
-            if let Some(csrf_cookie) = cookies.iter().find(|c| c.name() == "ct0") {
-                headers.insert(
-                    "x-csrf-token",
-                    HeaderValue::from_str(csrf_cookie.value())
-                        .map_err(|e| TwitterError::Auth(e.to_string()))?,
-                );
-            }
+    async fn prepare(&self, client: &TwitterClient) -> Result<()> {
+        if self.has_session().await {
+            return Ok(());
         }
+        self.ensure_guest_token(client).await
+    }
+
+    async fn install_headers(&self, headers: &mut HeaderMap) -> Result<()> {
+        self.install_cookie_headers(None, headers).await?;
         headers.insert(
             "Authorization",
             HeaderValue::from_str(&format!("Bearer {}", self.bearer_token))
                 .map_err(|e| TwitterError::Auth(e.to_string()))?,
         );
-        if let Some(token) = &self.guest_token {
+        let guest_token = self.guest_token.lock().unwrap().clone();
+        if let Some(guest) = guest_token {
             headers.insert(
                 "x-guest-token",
-                HeaderValue::from_str(token).map_err(|e| TwitterError::Auth(e.to_string()))?,
+                HeaderValue::from_str(&guest.token)
+                    .map_err(|e| TwitterError::Auth(e.to_string()))?,
             );
         }
         headers.insert("x-twitter-active-user", HeaderValue::from_static("yes"));
-        headers.insert("x-twitter-client-language", HeaderValue::from_static("en"));
+        headers.insert(
+            "x-twitter-client-language",
+            HeaderValue::from_str(&self.language).map_err(|e| TwitterError::Auth(e.to_string()))?,
+        );
         headers.insert(
             "x-twitter-auth-type",
             HeaderValue::from_static("OAuth2Client"),
@@ -618,14 +974,27 @@ impl TwitterAuth for TwitterUserAuth {
         Ok(())
     }
 
+    async fn refresh_headers(&self, url: &str, headers: &mut HeaderMap) -> Result<()> {
+        if headers.contains_key("cookie") {
+            headers.remove("cookie");
+            headers.remove("x-csrf-token");
+            self.install_cookie_headers(Some(url), headers).await?;
+        }
+        Ok(())
+    }
+
+    async fn capture_cookies(&self, url: &str, headers: &HeaderMap) -> Result<()> {
+        self.update_cookies_from_headers(url, headers).await;
+        Ok(())
+    }
+
     async fn get_cookies(&self) -> Result<Vec<cookie::Cookie<'_>>> {
         let jar = self.cookie_jar.lock().await;
-        Ok(jar.iter().map(|c| c.to_owned()).collect())
+        Ok(jar.iter().map(StoredCookie::to_cookie).collect())
     }
 
     fn delete_token(&mut self) {
-        self.guest_token = None;
-        self.created_at = None;
+        *self.guest_token.lock().unwrap() = None;
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -1,10 +1,19 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 #[derive(Debug, Error, Deserialize)]
 pub enum TwitterError {
     #[error("API error: {0}")]
     Api(String),
 
+    #[error("Request failed with status {status}{}", format_api_errors(.errors))]
+    #[serde(skip)]
+    ApiResponse {
+        status: u16,
+        errors: Vec<ApiErrorDetail>,
+        body: String,
+    },
+
     #[error("Authentication error: {0}")]
     Auth(String),
 
@@ -13,7 +22,7 @@ pub enum TwitterError {
     Network(#[from] reqwest::Error),
 
     #[error("Rate limit exceeded")]
-    RateLimit,
+    RateLimit { reset_at: Option<DateTime<Utc>> },
 
     #[error("Invalid response format: {0}")]
     InvalidResponse(String),
@@ -24,6 +33,9 @@ pub enum TwitterError {
     #[error("Cookie error: {0}")]
     Cookie(String),
 
+    #[error("Invalid tweet: {0}")]
+    InvalidTweet(String),
+
     #[error("JSON error: {0}")]
     #[serde(skip)]
     Json(#[from] serde_json::Error),
@@ -31,6 +43,208 @@ pub enum TwitterError {
     #[error("IO error: {0}")]
     #[serde(skip)]
     Io(#[from] std::io::Error),
+
+    /// A thread stopped at `failed_part`. `posted` holds the IDs of earlier
+    /// parts that are still live after any rollback.
+    #[error("Thread failed at part {failed_part} after {} posted: {source}", .posted.len())]
+    #[serde(skip)]
+    Thread {
+        posted: Vec<String>,
+        failed_part: usize,
+        rolled_back: bool,
+        source: Box<TwitterError>,
+    },
+}
+
+/// One entry of a Twitter `{"errors": [...]}` body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiErrorDetail {
+    pub code: Option<i32>,
+    #[serde(default)]
+    pub message: String,
+}
+
+impl ApiErrorDetail {
+    pub fn kind(&self) -> Option<ApiErrorKind> {
+        self.code.and_then(ApiErrorKind::from_code)
+    }
+}
+
+/// Causes callers commonly need to branch on, decoded from Twitter error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    AuthExpired,
+    /// The `x-csrf-token` header did not match the `ct0` cookie.
+    CsrfMismatch,
+    /// The authenticated account is suspended.
+    AccountSuspended,
+    /// The user being looked up is suspended; the session itself is fine.
+    UserSuspended,
+    AccountLocked,
+    ProtectedUser,
+    TweetNotFound,
+    DuplicateTweet,
+    RateLimited,
+}
+
+impl ApiErrorKind {
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            32 | 89 => Some(Self::AuthExpired),
+            353 => Some(Self::CsrfMismatch),
+            63 => Some(Self::UserSuspended),
+            64 => Some(Self::AccountSuspended),
+            326 => Some(Self::AccountLocked),
+            179 => Some(Self::ProtectedUser),
+            // 421 is GraphQL's "Tweet is not available", e.g. deleted or withheld.
+            144 | 421 => Some(Self::TweetNotFound),
+            187 => Some(Self::DuplicateTweet),
+            88 => Some(Self::RateLimited),
+            _ => None,
+        }
+    }
+}
+
+impl TwitterError {
+    /// Builds an `ApiResponse` error from a status code and raw response body,
+    /// decoding the `errors` array when the body has one.
+    pub fn from_response(status: u16, body: String) -> Self {
+        let errors = parse_api_errors(&body);
+        TwitterError::ApiResponse {
+            status,
+            errors,
+            body,
+        }
+    }
+
+    /// Wraps GraphQL errors that arrived next to `data` in an HTTP 200 body.
+    pub fn from_graphql_errors(errors: Vec<ApiErrorDetail>) -> Self {
+        let body = serde_json::json!({ "errors": errors }).to_string();
+        TwitterError::ApiResponse {
+            status: 200,
+            errors,
+            body,
+        }
+    }
+
+    pub fn kind(&self) -> Option<ApiErrorKind> {
+        match self {
+            TwitterError::ApiResponse { errors, .. } => errors.iter().find_map(|e| e.kind()),
+            TwitterError::RateLimit { .. } => Some(ApiErrorKind::RateLimited),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            TwitterError::ApiResponse { status, .. } => Some(*status),
+            TwitterError::RateLimit { .. } => Some(429),
+            TwitterError::Network(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Whether the session itself was rejected: a 401, an expired-auth error
+    /// code, or a 403 with no error code at all. A 403 carrying codes we don't
+    /// map (daily tweet limit, automated-request block, ...) is not an auth
+    /// failure, since logging in again would not fix it.
+    pub fn is_auth_failure(&self) -> bool {
+        match self.kind() {
+            Some(ApiErrorKind::AuthExpired) => true,
+            Some(_) => false,
+            None => match self.status() {
+                Some(401) => true,
+                Some(403) => self.error_codes().is_empty(),
+                _ => false,
+            },
+        }
+    }
+
+    pub fn error_codes(&self) -> Vec<i32> {
+        match self {
+            TwitterError::ApiResponse { errors, .. } => {
+                errors.iter().filter_map(|e| e.code).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub(crate) fn parse_api_errors(body: &str) -> Vec<ApiErrorDetail> {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        #[serde(default)]
+        errors: Vec<ApiErrorDetail>,
+    }
+
+    serde_json::from_str::<ErrorBody>(body)
+        .map(|parsed| parsed.errors)
+        .unwrap_or_default()
+}
+
+/// Fails when a GraphQL page came back empty alongside errors, so a broken
+/// response is not mistaken for the end of a timeline. Errors on a page that
+/// still has items are left for the caller to surface as warnings.
+pub(crate) fn check_partial_errors(errors: &[ApiErrorDetail], page_is_empty: bool) -> Result<()> {
+    if page_is_empty && !errors.is_empty() {
+        return Err(TwitterError::from_graphql_errors(errors.to_vec()));
+    }
+    Ok(())
+}
+
+fn format_api_errors(errors: &[ApiErrorDetail]) -> String {
+    if errors.is_empty() {
+        return String::new();
+    }
+    let details = errors
+        .iter()
+        .map(|e| match e.code {
+            Some(code) => format!("[{}] {}", code, e.message),
+            None => e.message.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!(": {}", details)
 }
 
 pub type Result<T> = std::result::Result<T, TwitterError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_error_codes() {
+        assert_eq!(ApiErrorKind::from_code(32), Some(ApiErrorKind::AuthExpired));
+        assert_eq!(ApiErrorKind::from_code(353), Some(ApiErrorKind::CsrfMismatch));
+        assert_eq!(ApiErrorKind::from_code(63), Some(ApiErrorKind::UserSuspended));
+        assert_eq!(ApiErrorKind::from_code(64), Some(ApiErrorKind::AccountSuspended));
+        assert_eq!(ApiErrorKind::from_code(421), Some(ApiErrorKind::TweetNotFound));
+        assert_eq!(ApiErrorKind::from_code(185), None);
+    }
+
+    #[test]
+    fn decodes_error_body() {
+        let body = r#"{"errors":[{"code":187,"message":"Status is a duplicate."}]}"#;
+        let err = TwitterError::from_response(403, body.to_string());
+        assert_eq!(err.status(), Some(403));
+        assert_eq!(err.error_codes(), vec![187]);
+        assert_eq!(err.kind(), Some(ApiErrorKind::DuplicateTweet));
+        assert!(!err.is_auth_failure());
+        assert!(err.to_string().contains("[187] Status is a duplicate."));
+    }
+
+    #[test]
+    fn classifies_auth_failures() {
+        let expired = r#"{"errors":[{"code":89,"message":"Invalid or expired token."}]}"#;
+        assert!(TwitterError::from_response(401, expired.to_string()).is_auth_failure());
+
+        let csrf = r#"{"errors":[{"code":353,"message":"This request requires a matching csrf cookie and header."}]}"#;
+        assert!(!TwitterError::from_response(403, csrf.to_string()).is_auth_failure());
+
+        let automated = r#"{"errors":[{"code":226,"message":"This request looks like it might be automated."}]}"#;
+        assert!(!TwitterError::from_response(403, automated.to_string()).is_auth_failure());
+
+        assert!(TwitterError::from_response(403, "Forbidden".to_string()).is_auth_failure());
+    }
+}
@@ -3,10 +3,15 @@ pub mod auth;
 pub mod constants;
 pub mod error;
 pub mod models;
+pub mod pagination;
+pub mod polls;
+pub mod pool;
 pub mod profile;
 pub mod relationships;
+pub mod scheduled;
 pub mod scraper;
 pub mod search;
+pub mod text;
 pub mod timeline;
 pub mod tweets;
 pub mod messages;
@@ -1,6 +1,5 @@
 use crate::api::client::TwitterClient;
 use crate::error::{Result, TwitterError};
-use chrono::{DateTime, Utc};
 use reqwest::header::HeaderMap;
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
@@ -140,17 +139,17 @@ pub async fn get_direct_message_conversations(
     cursor: Option<&str>,
 ) -> Result<DirectMessagesResponse> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
-    let message_list_url = "https://x.com/i/api/1.1/dm/inbox_initial_state.json";
+    let message_list_url = client.urls.x_url("/1.1/dm/inbox_initial_state.json");
     let url = if let Some(cursor_val) = cursor {
         format!("{}?cursor={}", message_list_url, cursor_val)
     } else {
-        message_list_url.to_string()
+        message_list_url
     };
 
     let (data, _) = crate::api::requests::request_api::<Value>(
-        &client.client,
+        client,
         &url,
         headers,
         Method::GET,
@@ -167,9 +166,9 @@ pub async fn send_direct_message(
     text: &str,
 ) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
-    let message_dm_url = "https://x.com/i/api/1.1/dm/new2.json";
+    let message_dm_url = client.urls.x_url("/1.1/dm/new2.json");
 
     let payload = json!({
         "conversation_id": conversation_id,
@@ -182,8 +181,8 @@ pub async fn send_direct_message(
     });
 
     let (response, _) = crate::api::requests::request_api::<Value>(
-        &client.client,
-        message_dm_url,
+        client,
+        &message_dm_url,
         headers,
         Method::POST,
         Some(payload),
@@ -0,0 +1,160 @@
+use crate::error::Result;
+use futures::stream::{self, Stream};
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+
+/// One page of a cursor-based endpoint.
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<String>,
+}
+
+struct PaginatorState<T, F> {
+    fetch: F,
+    cursor: Option<String>,
+    seen_cursors: HashSet<String>,
+    buffer: VecDeque<T>,
+    remaining: usize,
+    exhausted: bool,
+}
+
+/// Turns a page fetcher into a stream of items.
+///
+/// Follows the bottom cursor until a page comes back empty, the cursor is
+/// missing or repeats, or `max_items` have been yielded. A failed page is
+/// yielded as an error and ends the stream.
+pub fn paginate<'a, T, F, Fut>(max_items: usize, fetch: F) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<Page<T>>> + 'a,
+{
+    let state = PaginatorState {
+        fetch,
+        cursor: None,
+        seen_cursors: HashSet::new(),
+        buffer: VecDeque::new(),
+        remaining: max_items,
+        exhausted: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if state.remaining == 0 {
+                return None;
+            }
+
+            if let Some(item) = state.buffer.pop_front() {
+                state.remaining -= 1;
+                return Some((Ok(item), state));
+            }
+
+            if state.exhausted {
+                return None;
+            }
+
+            let page = match (state.fetch)(state.cursor.take()).await {
+                Ok(page) => page,
+                Err(err) => {
+                    state.exhausted = true;
+                    return Some((Err(err), state));
+                }
+            };
+
+            if page.items.is_empty() {
+                return None;
+            }
+            state.buffer.extend(page.items);
+
+            match page.next {
+                Some(next) if state.seen_cursors.insert(next.clone()) => {
+                    state.cursor = Some(next);
+                }
+                _ => state.exhausted = true,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TwitterError;
+    use futures::StreamExt;
+    use std::cell::RefCell;
+
+    /// Serves `pages` in order, recording the cursor each request was made
+    /// with.
+    fn fetcher(
+        pages: Vec<Result<Page<u32>>>,
+        cursors: &RefCell<Vec<Option<String>>>,
+    ) -> impl FnMut(Option<String>) -> futures::future::Ready<Result<Page<u32>>> + '_ {
+        let mut pages = pages.into_iter();
+        move |cursor| {
+            cursors.borrow_mut().push(cursor);
+            futures::future::ready(pages.next().expect("fetched past the last page"))
+        }
+    }
+
+    fn page(items: &[u32], next: Option<&str>) -> Result<Page<u32>> {
+        Ok(Page {
+            items: items.to_vec(),
+            next: next.map(String::from),
+        })
+    }
+
+    async fn collect(stream: impl Stream<Item = Result<u32>>) -> Vec<Result<u32>> {
+        stream.collect().await
+    }
+
+    #[tokio::test]
+    async fn follows_cursors_until_max_items() {
+        let cursors = RefCell::new(Vec::new());
+        let pages = vec![page(&[1, 2], Some("a")), page(&[3, 4], Some("b"))];
+        let items = collect(paginate(3, fetcher(pages, &cursors))).await;
+
+        let items: Vec<u32> = items.into_iter().map(|item| item.unwrap()).collect();
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(*cursors.borrow(), vec![None, Some("a".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn stops_on_empty_page() {
+        let cursors = RefCell::new(Vec::new());
+        let pages = vec![page(&[1], Some("a")), page(&[], Some("b"))];
+        let items = collect(paginate(10, fetcher(pages, &cursors))).await;
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(cursors.borrow().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stops_on_repeated_cursor() {
+        let cursors = RefCell::new(Vec::new());
+        let pages = vec![
+            page(&[1], Some("a")),
+            page(&[2], Some("b")),
+            page(&[3], Some("a")),
+        ];
+        let items = collect(paginate(10, fetcher(pages, &cursors))).await;
+
+        let items: Vec<u32> = items.into_iter().map(|item| item.unwrap()).collect();
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(cursors.borrow().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn yields_error_and_ends() {
+        let cursors = RefCell::new(Vec::new());
+        let pages = vec![
+            page(&[1, 2], Some("a")),
+            Err(TwitterError::Api("boom".into())),
+        ];
+        let items = collect(paginate(10, fetcher(pages, &cursors))).await;
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(*items[1].as_ref().unwrap(), 2);
+        assert!(matches!(items[2], Err(TwitterError::Api(_))));
+        assert_eq!(cursors.borrow().len(), 2);
+    }
+}
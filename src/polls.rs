@@ -0,0 +1,268 @@
+use crate::api::client::TwitterClient;
+use crate::api::endpoints::Endpoints;
+use crate::api::requests::{request_api, request_form_api};
+use crate::error::{Result, TwitterError};
+use crate::models::{PollOption, PollV2};
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use serde_json::{json, Value};
+
+pub const MIN_POLL_CHOICES: usize = 2;
+pub const MAX_POLL_CHOICES: usize = 4;
+pub const MAX_CHOICE_LENGTH: usize = 25;
+/// Polls run between five minutes and seven days.
+pub const MIN_POLL_MINUTES: u32 = 5;
+pub const MAX_POLL_MINUTES: u32 = 7 * 24 * 60;
+
+/// A poll to attach to a new tweet.
+#[derive(Debug, Clone)]
+pub struct NewPoll {
+    pub choices: Vec<String>,
+    pub duration_minutes: u32,
+}
+
+impl NewPoll {
+    pub fn new<I, S>(choices: I, duration_minutes: u32) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            choices: choices.into_iter().map(Into::into).collect(),
+            duration_minutes,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_POLL_CHOICES..=MAX_POLL_CHOICES).contains(&self.choices.len()) {
+            return Err(TwitterError::InvalidTweet(format!(
+                "A poll needs {} to {} choices, got {}",
+                MIN_POLL_CHOICES,
+                MAX_POLL_CHOICES,
+                self.choices.len()
+            )));
+        }
+        for choice in &self.choices {
+            if choice.trim().is_empty() {
+                return Err(TwitterError::InvalidTweet(
+                    "Poll choices cannot be empty".into(),
+                ));
+            }
+            crate::text::validate_length(choice, MAX_CHOICE_LENGTH)?;
+        }
+        if !(MIN_POLL_MINUTES..=MAX_POLL_MINUTES).contains(&self.duration_minutes) {
+            return Err(TwitterError::InvalidTweet(format!(
+                "Poll duration must be {} to {} minutes",
+                MIN_POLL_MINUTES, MAX_POLL_MINUTES
+            )));
+        }
+        Ok(())
+    }
+
+    fn card_data(&self) -> Value {
+        let mut data = json!({
+            "twitter:card": format!("poll{}choice_text_only", self.choices.len()),
+            "twitter:api:api:endpoint": "1",
+            "twitter:long:duration_minutes": self.duration_minutes,
+        });
+        for (index, choice) in self.choices.iter().enumerate() {
+            data[format!("twitter:string:choice{}_label", index + 1)] = json!(choice);
+        }
+        data
+    }
+}
+
+/// Creates the poll card that CreateTweet attaches by its `card_uri`.
+pub async fn create_poll_card(client: &TwitterClient, poll: &NewPoll) -> Result<String> {
+    poll.validate()?;
+
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let form = vec![("card_data".to_string(), poll.card_data().to_string())];
+    let (response, _) = request_form_api::<Value>(
+        client,
+        &client.urls.caps_url("/v2/cards/create.json"),
+        headers,
+        form,
+    )
+    .await?;
+
+    response["card_uri"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| TwitterError::InvalidResponse("Poll card response has no card_uri".into()))
+}
+
+/// Fetches the current state of a tweet's poll, including live vote counts.
+/// Returns `None` when the tweet has no poll.
+pub async fn fetch_poll(client: &TwitterClient, tweet_id: &str) -> Result<Option<PollV2>> {
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let url = Endpoints::tweet_by_rest_id(&client.urls, tweet_id).to_request_url();
+    let (response, _) = request_api::<Value>(client, &url, headers, Method::GET, None).await?;
+
+    let result = &response["data"]["tweetResult"]["result"];
+    // Tweets with visibility limits nest the tweet one level deeper.
+    let card = match result.get("card") {
+        Some(card) => card,
+        None => &result["tweet"]["card"],
+    };
+    Ok(parse_poll_card(card))
+}
+
+/// Reads a poll from a GraphQL card object, or `None` if the card is not a
+/// poll.
+pub fn parse_poll_card(card: &Value) -> Option<PollV2> {
+    let legacy = &card["legacy"];
+    if !legacy["name"].as_str()?.starts_with("poll") {
+        return None;
+    }
+
+    let bindings = legacy["binding_values"].as_array()?;
+    let binding = |key: &str| {
+        bindings
+            .iter()
+            .find(|binding| binding["key"] == key)
+            .map(|binding| &binding["value"])
+    };
+    let string = |key: &str| binding(key).and_then(|value| value["string_value"].as_str());
+
+    let options = (1..=MAX_POLL_CHOICES)
+        .map_while(|position| {
+            let label = string(&format!("choice{}_label", position))?;
+            Some(PollOption {
+                position: Some(position as i32),
+                label: label.to_string(),
+                votes: string(&format!("choice{}_count", position))
+                    .and_then(|count| count.parse().ok()),
+            })
+        })
+        .collect();
+
+    let counts_are_final = binding("counts_are_final")
+        .and_then(|value| value["boolean_value"].as_bool())
+        .unwrap_or(false);
+
+    Some(PollV2 {
+        id: card["rest_id"].as_str().map(String::from),
+        end_datetime: string("end_datetime_utc").map(String::from),
+        voting_status: Some(if counts_are_final { "closed" } else { "open" }.to_string()),
+        options,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_binding(key: &str, value: &str) -> Value {
+        json!({ "key": key, "value": { "string_value": value, "type": "STRING" } })
+    }
+
+    #[test]
+    fn parses_two_choice_card() {
+        let card = json!({
+            "rest_id": "card://1850000000000000001",
+            "legacy": {
+                "name": "poll2choice_text_only",
+                "url": "card://1850000000000000001",
+                "binding_values": [
+                    string_binding("choice1_label", "Tabs"),
+                    string_binding("choice1_count", "12"),
+                    string_binding("choice2_label", "Spaces"),
+                    string_binding("choice2_count", "30"),
+                    string_binding("end_datetime_utc", "2026-10-18T12:00:00Z"),
+                    string_binding("duration_minutes", "1440"),
+                    { "key": "counts_are_final", "value": { "boolean_value": false, "type": "BOOLEAN" } },
+                    { "key": "api", "value": { "string_value": "capi://passthrough/1", "type": "STRING" } }
+                ]
+            }
+        });
+
+        let poll = parse_poll_card(&card).unwrap();
+        assert_eq!(poll.id.as_deref(), Some("card://1850000000000000001"));
+        assert_eq!(poll.end_datetime.as_deref(), Some("2026-10-18T12:00:00Z"));
+        assert_eq!(poll.voting_status.as_deref(), Some("open"));
+        let options: Vec<_> = poll
+            .options
+            .iter()
+            .map(|option| (option.position, option.label.as_str(), option.votes))
+            .collect();
+        assert_eq!(
+            options,
+            [(Some(1), "Tabs", Some(12)), (Some(2), "Spaces", Some(30))]
+        );
+    }
+
+    #[test]
+    fn parses_closed_four_choice_card() {
+        let mut bindings: Vec<Value> = (1..=4)
+            .flat_map(|position| {
+                [
+                    string_binding(
+                        &format!("choice{}_label", position),
+                        &format!("Option {}", position),
+                    ),
+                    string_binding(&format!("choice{}_count", position), &position.to_string()),
+                ]
+            })
+            .collect();
+        bindings.push(json!({ "key": "counts_are_final", "value": { "boolean_value": true, "type": "BOOLEAN" } }));
+        let card = json!({
+            "rest_id": "card://1850000000000000002",
+            "legacy": { "name": "poll4choice_text_only", "binding_values": bindings }
+        });
+
+        let poll = parse_poll_card(&card).unwrap();
+        assert_eq!(poll.voting_status.as_deref(), Some("closed"));
+        assert_eq!(poll.options.len(), 4);
+        assert_eq!(poll.options[3].label, "Option 4");
+        assert_eq!(poll.options[3].votes, Some(4));
+    }
+
+    #[test]
+    fn ignores_cards_that_are_not_polls() {
+        let card = json!({
+            "legacy": {
+                "name": "summary_large_image",
+                "binding_values": [string_binding("title", "A link")]
+            }
+        });
+        assert!(parse_poll_card(&card).is_none());
+        assert!(parse_poll_card(&Value::Null).is_none());
+    }
+
+    #[test]
+    fn validates_choice_count() {
+        assert!(NewPoll::new(["Yes", "No"], 60).validate().is_ok());
+        assert!(NewPoll::new(["a", "b", "c", "d"], 60).validate().is_ok());
+        assert!(NewPoll::new(["Only one"], 60).validate().is_err());
+        assert!(NewPoll::new(["a", "b", "c", "d", "e"], 60)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn validates_choice_length() {
+        let longest = "x".repeat(MAX_CHOICE_LENGTH);
+        let too_long = "x".repeat(MAX_CHOICE_LENGTH + 1);
+        assert!(NewPoll::new([longest.as_str(), "No"], 60)
+            .validate()
+            .is_ok());
+        assert!(NewPoll::new([too_long.as_str(), "No"], 60)
+            .validate()
+            .is_err());
+        assert!(NewPoll::new(["  ", "No"], 60).validate().is_err());
+    }
+
+    #[test]
+    fn validates_duration() {
+        let poll = |minutes| NewPoll::new(["Yes", "No"], minutes).validate();
+        assert!(poll(MIN_POLL_MINUTES).is_ok());
+        assert!(poll(MAX_POLL_MINUTES).is_ok());
+        assert!(poll(MIN_POLL_MINUTES - 1).is_err());
+        assert!(poll(MAX_POLL_MINUTES + 1).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::api::client::TwitterClient;
+use crate::error::{ApiErrorKind, Result, TwitterError};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// Cycle through healthy accounts in order.
+    #[default]
+    RoundRobin,
+    /// Prefer the account whose last rate limit is furthest in the past.
+    LeastRecentlyLimited,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountHealth {
+    Healthy,
+    /// Skipped until `until`, or until released when `until` is `None`.
+    Quarantined {
+        reason: String,
+        until: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Debug, Default)]
+struct AccountState {
+    last_limited: Option<DateTime<Utc>>,
+    quarantine: Option<(String, Option<DateTime<Utc>>)>,
+}
+
+impl AccountState {
+    fn is_available(&self) -> bool {
+        match &self.quarantine {
+            None => true,
+            Some((_, Some(until))) => *until <= Utc::now(),
+            Some((_, None)) => false,
+        }
+    }
+}
+
+struct PoolAccount {
+    name: String,
+    client: TwitterClient,
+    state: Mutex<AccountState>,
+}
+
+/// Several logged-in clients, each with its own session and rate limit
+/// state, used in turn for requests.
+///
+/// A rate-limited request is retried on the next account. Accounts that fail
+/// auth are quarantined for `auth_quarantine`; locked or suspended accounts
+/// are quarantined until `release` is called.
+pub struct AccountPool {
+    accounts: Vec<PoolAccount>,
+    strategy: SelectionStrategy,
+    auth_quarantine: Duration,
+    next: AtomicUsize,
+}
+
+impl AccountPool {
+    pub fn new(strategy: SelectionStrategy) -> Self {
+        Self {
+            accounts: Vec::new(),
+            strategy,
+            auth_quarantine: Duration::from_secs(15 * 60),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_auth_quarantine(mut self, duration: Duration) -> Self {
+        self.auth_quarantine = duration;
+        self
+    }
+
+    pub fn add_account(&mut self, name: impl Into<String>, client: TwitterClient) {
+        self.accounts.push(PoolAccount {
+            name: name.into(),
+            client,
+            state: Mutex::new(AccountState::default()),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn client(&self, name: &str) -> Option<&TwitterClient> {
+        self.find(name).map(|account| &account.client)
+    }
+
+    pub fn health(&self) -> Vec<(String, AccountHealth)> {
+        self.accounts
+            .iter()
+            .map(|account| {
+                let state = account.state.lock().unwrap();
+                let health = match &state.quarantine {
+                    Some((reason, until)) if !state.is_available() => AccountHealth::Quarantined {
+                        reason: reason.clone(),
+                        until: *until,
+                    },
+                    _ => AccountHealth::Healthy,
+                };
+                (account.name.clone(), health)
+            })
+            .collect()
+    }
+
+    /// Takes an account out of quarantine.
+    pub fn release(&self, name: &str) {
+        if let Some(account) = self.find(name) {
+            account.state.lock().unwrap().quarantine = None;
+        }
+    }
+
+    /// Runs `op` on a selected account, moving on to the next one when it is
+    /// rate limited or its session is rejected.
+    pub async fn run<'a, T, F, Fut>(&'a self, op: F) -> Result<T>
+    where
+        F: Fn(&'a TwitterClient) -> Fut,
+        Fut: Future<Output = Result<T>> + 'a,
+    {
+        let mut tried = vec![false; self.accounts.len()];
+        let mut last_error = None;
+
+        while let Some(index) = self.select(&tried) {
+            tried[index] = true;
+            let account = &self.accounts[index];
+
+            let err = match op(&account.client).await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+
+            if !self.record_failure(account, &err) {
+                return Err(err);
+            }
+            tracing::debug!(account = %account.name, error = %err, "Trying next pool account");
+            last_error = Some(err);
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| TwitterError::Auth("No healthy accounts in pool".into())))
+    }
+
+    fn find(&self, name: &str) -> Option<&PoolAccount> {
+        self.accounts.iter().find(|account| account.name == name)
+    }
+
+    fn select(&self, tried: &[bool]) -> Option<usize> {
+        let count = self.accounts.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut candidates = (0..count)
+            .map(|offset| (start + offset) % count)
+            .filter(|&index| !tried[index])
+            .filter(|&index| self.accounts[index].state.lock().unwrap().is_available());
+
+        match self.strategy {
+            SelectionStrategy::RoundRobin => candidates.next(),
+            // `min_by_key` keeps the first of equal keys, so ties still rotate.
+            SelectionStrategy::LeastRecentlyLimited => candidates
+                .min_by_key(|&index| self.accounts[index].state.lock().unwrap().last_limited),
+        }
+    }
+
+    /// Updates the account after a failed request. Returns whether another
+    /// account may succeed where this one failed.
+    fn record_failure(&self, account: &PoolAccount, err: &TwitterError) -> bool {
+        let mut state = account.state.lock().unwrap();
+        match err.kind() {
+            Some(ApiErrorKind::RateLimited) => {
+                state.last_limited = Some(Utc::now());
+                true
+            }
+            Some(kind @ (ApiErrorKind::AccountLocked | ApiErrorKind::AccountSuspended)) => {
+                tracing::warn!(account = %account.name, ?kind, "Quarantining pool account");
+                state.quarantine = Some((format!("{:?}", kind), None));
+                true
+            }
+            _ if err.is_auth_failure() => {
+                tracing::warn!(account = %account.name, error = %err, "Quarantining pool account");
+                let until = chrono::Duration::from_std(self.auth_quarantine)
+                    .ok()
+                    .map(|duration| Utc::now() + duration);
+                state.quarantine = Some((err.to_string(), until));
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::api::requests::request_api;
-use crate::error::{Result, TwitterError};
+use crate::api::v2::{FieldSelection, UserLookup};
+use crate::auth::user_auth::TwitterUserAuth;
+use crate::error::{ApiErrorDetail, Result, TwitterError};
 use crate::models::Profile;
 use chrono::{DateTime, Utc};
 use lazy_static::lazy_static;
@@ -116,13 +118,16 @@ pub fn parse_profile(user: &LegacyUserRaw, is_blue_verified: Option<bool>) -> Pr
     };
 
     // Set website URL from entities using functional chaining
-    user.entities
+    if let Some(expanded_url) = user
+        .entities
         .as_ref()
         .and_then(|entities| entities.url.as_ref())
         .and_then(|url_entity| url_entity.urls.as_ref())
         .and_then(|urls| urls.first())
         .and_then(|first_url| first_url.expanded_url.as_ref())
-        .map(|expanded_url| profile.url = Some(expanded_url.clone()));
+    {
+        profile.url = Some(expanded_url.clone());
+    }
 
     profile
 }
@@ -134,6 +139,7 @@ pub struct UserResults {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "__typename")]
+#[allow(clippy::large_enum_variant)]
 pub enum UserResult {
     User(UserData),
     UserUnavailable(UserUnavailable),
@@ -188,9 +194,21 @@ pub struct TwitterApiErrorRaw {
     pub code: i32,
 }
 
+fn api_errors_to_error(errors: Vec<TwitterApiErrorRaw>) -> TwitterError {
+    TwitterError::from_graphql_errors(
+        errors
+            .into_iter()
+            .map(|e| ApiErrorDetail {
+                code: Some(e.code),
+                message: e.message,
+            })
+            .collect(),
+    )
+}
+
 pub async fn get_profile(client: &TwitterClient, screen_name: &str) -> Result<Profile> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let variables = json!({
         "screen_name": screen_name,
@@ -215,8 +233,8 @@ pub async fn get_profile(client: &TwitterClient, screen_name: &str) -> Result<Pr
     });
 
     let (response, _) = request_api::<UserRaw>(
-        &client.client,
-        "https://twitter.com/i/api/graphql/G3KGOASz96M-Qu0nwmGXNg/UserByScreenName",
+        client,
+        &client.urls.graphql_url("/G3KGOASz96M-Qu0nwmGXNg/UserByScreenName"),
         headers,
         Method::GET,
         Some(json!({
@@ -229,7 +247,7 @@ pub async fn get_profile(client: &TwitterClient, screen_name: &str) -> Result<Pr
 
     if let Some(errors) = response.errors {
         if !errors.is_empty() {
-            return Err(TwitterError::Api(errors[0].message.clone()));
+            return Err(api_errors_to_error(errors));
         }
     }
     let user_raw_result = &response.data.user.result;
@@ -248,7 +266,7 @@ pub async fn get_profile(client: &TwitterClient, screen_name: &str) -> Result<Pr
 
 pub async fn get_screen_name_by_user_id(client: &TwitterClient, user_id: &str) -> Result<String> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let variables = json!({
         "userId": user_id,
@@ -269,8 +287,8 @@ pub async fn get_screen_name_by_user_id(client: &TwitterClient, user_id: &str) -
     });
 
     let (response, _) = request_api::<UserRaw>(
-        &client.client,
-        "https://twitter.com/i/api/graphql/xf3jd90KKBCUxdlI_tNHZw/UserByRestId",
+        client,
+        &client.urls.graphql_url("/xf3jd90KKBCUxdlI_tNHZw/UserByRestId"),
         headers,
         Method::GET,
         Some(json!({
@@ -282,7 +300,7 @@ pub async fn get_screen_name_by_user_id(client: &TwitterClient, user_id: &str) -
 
     if let Some(errors) = response.errors {
         if !errors.is_empty() {
-            return Err(TwitterError::Api(errors[0].message.clone()));
+            return Err(api_errors_to_error(errors));
         }
     }
 
@@ -304,8 +322,26 @@ pub async fn get_user_id_by_screen_name(
         return Ok(cached_id.clone());
     }
 
-    let profile = get_profile(client, screen_name).await?;
-    if let Some(user_id) = Some(profile.id) {
+    // The GraphQL lookup only accepts a cookie session; other auth goes
+    // through the official v2 endpoint.
+    let is_user_auth = client
+        .auth
+        .as_any()
+        .downcast_ref::<TwitterUserAuth>()
+        .is_some();
+    let user_id = if is_user_auth {
+        Some(get_profile(client, screen_name).await?.id)
+    } else {
+        UserLookup::by_usernames([screen_name])
+            .with_fields(FieldSelection::none())
+            .send(client)
+            .await?
+            .data
+            .into_iter()
+            .next()
+            .map(|profile| profile.id)
+    };
+    if let Some(user_id) = user_id {
         ID_CACHE
             .lock()
             .unwrap()
@@ -1,6 +1,6 @@
 use crate::api::requests::request_api;
 use crate::api::requests::request_form_api;
-use crate::error::{Result, TwitterError};
+use crate::error::{check_partial_errors, ApiErrorDetail, Result};
 use crate::models::Profile;
 use crate::timeline::v1::QueryProfilesResponse;
 use chrono::{DateTime, Utc};
@@ -11,8 +11,7 @@ use crate::api::client::TwitterClient;
 #[derive(Debug, Deserialize)]
 pub struct RelationshipResponse {
     pub data: Option<RelationshipData>,
-    #[serde(skip)]
-    pub errors: Option<Vec<TwitterError>>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,6 +42,7 @@ pub struct TimelineData {
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum TimelineInstruction {
     #[serde(rename = "TimelineAddEntries")]
     AddEntries { entries: Vec<TimelineEntry> },
@@ -146,7 +146,7 @@ pub struct CursorContent {
 #[derive(Debug, Deserialize)]
 pub struct RelationshipTimeline {
     pub data: Option<RelationshipTimelineData>,
-    pub errors: Option<Vec<TwitterError>>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -171,6 +171,7 @@ pub struct InnerTimeline {
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum Instruction {
     #[serde(rename = "TimelineAddEntries")]
     AddEntries {
@@ -211,23 +212,53 @@ pub async fn get_followers(
     count: i32,
     cursor: Option<String>,
 ) -> Result<(Vec<Profile>, Option<String>)> {
-    let response = fetch_profile_following(client, user_id, count, cursor).await?;
+    let response = fetch_profile_followers(client, user_id, count, cursor).await?;
     Ok((response.profiles, response.next))
 }
 
+pub async fn fetch_profile_followers(
+    client: &TwitterClient,
+    user_id: &str,
+    max_profiles: i32,
+    cursor: Option<String>,
+) -> Result<QueryProfilesResponse> {
+    let timeline = get_relationship_timeline(
+        client,
+        "/rRXFSG5vR6drKr5M37YOTw/Followers",
+        user_id,
+        max_profiles,
+        cursor,
+    )
+    .await?;
+    let response = parse_relationship_timeline(&timeline);
+    check_partial_errors(&response.warnings, response.profiles.is_empty())?;
+
+    Ok(response)
+}
+
 pub async fn fetch_profile_following(
     client: &TwitterClient,
     user_id: &str,
     max_profiles: i32,
     cursor: Option<String>,
 ) -> Result<QueryProfilesResponse> {
-    let timeline = get_following_timeline(client, user_id, max_profiles, cursor).await?;
+    let timeline = get_relationship_timeline(
+        client,
+        "/iSicc7LrzWGBgDPL0tM_TQ/Following",
+        user_id,
+        max_profiles,
+        cursor,
+    )
+    .await?;
+    let response = parse_relationship_timeline(&timeline);
+    check_partial_errors(&response.warnings, response.profiles.is_empty())?;
 
-    Ok(parse_relationship_timeline(&timeline))
+    Ok(response)
 }
 
-async fn get_following_timeline(
+async fn get_relationship_timeline(
     client: &TwitterClient,
+    operation: &str,
     user_id: &str,
     max_items: i32,
     cursor: Option<String>,
@@ -255,15 +286,16 @@ async fn get_following_timeline(
     });
 
     let url = format!(
-        "https://twitter.com/i/api/graphql/iSicc7LrzWGBgDPL0tM_TQ/Following?variables={}&features={}",
+        "{}?variables={}&features={}",
+        client.urls.graphql_url(operation),
         urlencoding::encode(&variables.to_string()),
         urlencoding::encode(&features.to_string())
     );
 
     let mut headers = reqwest::header::HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
-    let (_data, _) = request_api::<RelationshipTimeline>(&client.client, &url, headers, Method::GET, None).await?;
+    let (_data, _) = request_api::<RelationshipTimeline>(client, &url, headers, Method::GET, None).await?;
 
     Ok(_data)
 }
@@ -348,13 +380,14 @@ fn parse_relationship_timeline(timeline: &RelationshipTimeline) -> QueryProfiles
         profiles,
         next: next_cursor,
         previous: previous_cursor,
+        warnings: timeline.errors.clone().unwrap_or_default(),
     }
 }
 
 pub async fn follow_user(client: &TwitterClient, username: &str) -> Result<()> {
     let user_id = crate::profile::get_user_id_by_screen_name(client, username).await?;
 
-    let url = "https://api.twitter.com/1.1/friendships/create.json";
+    let url = client.urls.api_url("/1.1/friendships/create.json");
 
     let form = vec![
         (
@@ -366,7 +399,7 @@ pub async fn follow_user(client: &TwitterClient, username: &str) -> Result<()> {
     ];
 
     let mut headers = reqwest::header::HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     headers.insert(
         "Content-Type",
@@ -377,10 +410,8 @@ pub async fn follow_user(client: &TwitterClient, username: &str) -> Result<()> {
         format!("https://twitter.com/{}", username).parse().unwrap(),
     );
     headers.insert("X-Twitter-Active-User", "yes".parse().unwrap());
-    headers.insert("X-Twitter-Auth-Type", "OAuth2Session".parse().unwrap());
-    headers.insert("X-Twitter-Client-Language", "en".parse().unwrap());
 
-    let (_, _) = request_form_api::<Value>(&client.client, url, headers, form).await?;
+    let (_, _) = request_form_api::<Value>(client, &url, headers, form).await?;
 
     Ok(())
 }
@@ -389,7 +420,7 @@ pub async fn unfollow_user(client: &TwitterClient, username: &str) -> Result<()>
 
     let user_id = crate::profile::get_user_id_by_screen_name(client, username).await?;
 
-    let url = "https://api.twitter.com/1.1/friendships/destroy.json";
+    let url = client.urls.api_url("/1.1/friendships/destroy.json");
 
     let form = vec![
         (
@@ -401,7 +432,7 @@ pub async fn unfollow_user(client: &TwitterClient, username: &str) -> Result<()>
     ];
 
     let mut headers = reqwest::header::HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     headers.insert(
         "Content-Type",
@@ -412,10 +443,8 @@ pub async fn unfollow_user(client: &TwitterClient, username: &str) -> Result<()>
         format!("https://twitter.com/{}", username).parse().unwrap(),
     );
     headers.insert("X-Twitter-Active-User", "yes".parse().unwrap());
-    headers.insert("X-Twitter-Auth-Type", "OAuth2Session".parse().unwrap());
-    headers.insert("X-Twitter-Client-Language", "en".parse().unwrap());
 
-    let (_, _) = request_form_api::<Value>(&client.client, url, headers, form).await?;
+    let (_, _) = request_form_api::<Value>(client, &url, headers, form).await?;
 
     Ok(())
 }
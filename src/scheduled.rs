@@ -0,0 +1,255 @@
+use crate::api::client::TwitterClient;
+use crate::api::requests::request_api;
+use crate::error::{Result, TwitterError};
+use crate::tweets::check_mutation;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// A tweet queued to be posted at `execute_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTweet {
+    pub id: String,
+    pub text: String,
+    pub media_ids: Vec<String>,
+    pub execute_at: Option<DateTime<Utc>>,
+    /// Twitter's scheduling state, e.g. `"Scheduled"` or `"Failed"`.
+    pub state: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftTweet {
+    pub id: String,
+    pub text: String,
+    pub media_ids: Vec<String>,
+}
+
+/// Schedules `text` with media uploaded through `upload_media`, returning
+/// the scheduled tweet's ID.
+pub async fn create_scheduled_tweet(
+    client: &TwitterClient,
+    text: &str,
+    media_ids: &[String],
+    execute_at: DateTime<Utc>,
+) -> Result<String> {
+    validate_schedule(text, media_ids, execute_at)?;
+
+    let value = graphql_mutation(
+        client,
+        "/LCVzRQGxOaGnOnYH01NQXg/CreateScheduledTweet",
+        json!({
+            "post_tweet_request": post_tweet_request(text, media_ids),
+            "execute_at": execute_at.timestamp(),
+        }),
+    )
+    .await?;
+
+    created_id(&value)
+}
+
+pub async fn fetch_scheduled_tweets(client: &TwitterClient) -> Result<Vec<ScheduledTweet>> {
+    let value = graphql_query(
+        client,
+        "/ITtjAzvlZni2wWXwf295Qg/FetchScheduledTweets",
+        json!({ "ascending": true }),
+    )
+    .await?;
+    check_mutation(&value, "viewer")?;
+
+    let entries = value["data"]["viewer"]["scheduled_tweet_list"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let request = &entry["tweet_create_request"];
+            Some(ScheduledTweet {
+                id: entry["rest_id"].as_str()?.to_string(),
+                text: request["status"].as_str().unwrap_or_default().to_string(),
+                media_ids: string_list(&request["media_ids"]),
+                execute_at: entry["scheduling_info"]["execute_at"]
+                    .as_i64()
+                    .and_then(|millis| Utc.timestamp_millis_opt(millis).single()),
+                state: entry["scheduling_info"]["state"].as_str().map(String::from),
+            })
+        })
+        .collect())
+}
+
+/// Replaces the text, media and time of a scheduled tweet.
+pub async fn edit_scheduled_tweet(
+    client: &TwitterClient,
+    scheduled_tweet_id: &str,
+    text: &str,
+    media_ids: &[String],
+    execute_at: DateTime<Utc>,
+) -> Result<()> {
+    validate_schedule(text, media_ids, execute_at)?;
+
+    let value = graphql_mutation(
+        client,
+        "/_mHkQ5LHpRRjSXKOcG6eZw/EditScheduledTweet",
+        json!({
+            "scheduled_tweet_id": scheduled_tweet_id,
+            "post_tweet_request": post_tweet_request(text, media_ids),
+            "execute_at": execute_at.timestamp(),
+        }),
+    )
+    .await?;
+
+    check_mutation(&value, "scheduledtweet_put")
+}
+
+pub async fn delete_scheduled_tweet(client: &TwitterClient, scheduled_tweet_id: &str) -> Result<()> {
+    let value = graphql_mutation(
+        client,
+        "/CTOVqej0JBXAZSwkp1US0g/DeleteScheduledTweet",
+        json!({ "scheduled_tweet_id": scheduled_tweet_id }),
+    )
+    .await?;
+
+    check_mutation(&value, "scheduledtweet_delete")
+}
+
+pub async fn create_draft_tweet(client: &TwitterClient, text: &str, media_ids: &[String]) -> Result<String> {
+    crate::text::validate_tweet(text, !media_ids.is_empty())?;
+
+    let value = graphql_mutation(
+        client,
+        "/cH9HZWz_EW9gnswvA4ZRiQ/CreateDraftTweet",
+        json!({ "post_tweet_request": post_tweet_request(text, media_ids) }),
+    )
+    .await?;
+
+    created_id(&value)
+}
+
+pub async fn fetch_draft_tweets(client: &TwitterClient) -> Result<Vec<DraftTweet>> {
+    let value = graphql_query(
+        client,
+        "/ZkqIq_xRhiUme0PBJNpRtg/FetchDraftTweets",
+        json!({ "ascending": false }),
+    )
+    .await?;
+    check_mutation(&value, "viewer")?;
+
+    let entries = value["data"]["viewer"]["draft_list"]["response_data"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let request = &entry["tweet_create_request"];
+            Some(DraftTweet {
+                id: entry["rest_id"].as_str()?.to_string(),
+                text: request["status"].as_str().unwrap_or_default().to_string(),
+                media_ids: string_list(&request["media_ids"]),
+            })
+        })
+        .collect())
+}
+
+pub async fn edit_draft_tweet(
+    client: &TwitterClient,
+    draft_tweet_id: &str,
+    text: &str,
+    media_ids: &[String],
+) -> Result<()> {
+    crate::text::validate_tweet(text, !media_ids.is_empty())?;
+
+    let value = graphql_mutation(
+        client,
+        "/JIeXE-I6BZXHfxsgOkyHYQ/EditDraftTweet",
+        json!({
+            "draft_tweet_id": draft_tweet_id,
+            "post_tweet_request": post_tweet_request(text, media_ids),
+        }),
+    )
+    .await?;
+
+    check_mutation(&value, "drafttweet_put")
+}
+
+pub async fn delete_draft_tweet(client: &TwitterClient, draft_tweet_id: &str) -> Result<()> {
+    let value = graphql_mutation(
+        client,
+        "/bkh9G3FGgTldS9iTKWWYYw/DeleteDraftTweet",
+        json!({ "draft_tweet_id": draft_tweet_id }),
+    )
+    .await?;
+
+    check_mutation(&value, "drafttweet_delete")
+}
+
+fn validate_schedule(text: &str, media_ids: &[String], execute_at: DateTime<Utc>) -> Result<()> {
+    crate::text::validate_tweet(text, !media_ids.is_empty())?;
+    if execute_at <= Utc::now() {
+        return Err(TwitterError::InvalidTweet(
+            "Scheduled time must be in the future".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn post_tweet_request(text: &str, media_ids: &[String]) -> Value {
+    json!({
+        "auto_populate_reply_metadata": false,
+        "status": text,
+        "exclude_reply_user_ids": [],
+        "media_ids": media_ids,
+    })
+}
+
+/// Reads the ID of a newly created scheduled or draft tweet.
+fn created_id(value: &Value) -> Result<String> {
+    check_mutation(value, "tweet")?;
+    value["data"]["tweet"]["rest_id"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| TwitterError::InvalidResponse("Response has no tweet rest_id".into()))
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn graphql_mutation(client: &TwitterClient, path: &str, variables: Value) -> Result<Value> {
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let (value, _headers) = request_api(
+        client,
+        &client.urls.graphql_url(path),
+        headers,
+        Method::POST,
+        Some(json!({ "variables": variables })),
+    )
+    .await?;
+    Ok(value)
+}
+
+async fn graphql_query(client: &TwitterClient, path: &str, variables: Value) -> Result<Value> {
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let url = format!(
+        "{}?variables={}",
+        client.urls.graphql_url(path),
+        urlencoding::encode(&variables.to_string())
+    );
+
+    let (value, _headers) = request_api(client, &url, headers, Method::GET, None).await?;
+    Ok(value)
+}
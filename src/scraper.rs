@@ -1,25 +1,308 @@
 use crate::api::client::TwitterClient;
-use crate::auth::user_auth::TwitterUserAuth;
+use crate::api::rate_limit::{RateLimitStatus, RateLimits};
+use crate::api::retry::RetryPolicy;
+use crate::api::urls::BaseUrls;
+use crate::auth::challenge::{ChallengeResponse, LoginChallengeHandler};
+use crate::auth::config::{CredentialsProvider, LoginCredentials};
+use crate::auth::login_flow::LoginFlow;
+use crate::auth::session_store::SessionStore;
+use crate::auth::user_auth::{TwitterAuth, TwitterUserAuth};
 use crate::constants::BEARER_TOKEN;
 use crate::error::Result;
 use crate::error::TwitterError;
 use crate::messages::DirectMessagesResponse;
-use crate::models::{Profile, Tweet};
+use crate::models::{PollV2, Profile, Tweet};
+use crate::pagination::{paginate, Page};
+use crate::polls::NewPoll;
+use crate::pool::AccountPool;
+use crate::scheduled::{DraftTweet, ScheduledTweet};
 use crate::search::{fetch_search_tweets, SearchMode};
-use crate::timeline::home::TwitterResponse;
+use crate::text::{split_text, SplitOptions};
+use crate::timeline::home::HomeTimelinePage;
+use crate::tweets::{ThreadOptions, ThreadPart, TweetDetail};
 use crate::timeline::v1::{QueryProfilesResponse, QueryTweetsResponse};
 use crate::timeline::v2::QueryTweetsResponse as V2QueryTweetsResponse;
+use chrono::{DateTime, Utc};
+use futures::Stream;
 use serde_json::Value;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 pub struct Scraper {
     pub twitter_client: TwitterClient,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
+    keep_credentials: bool,
+    cookie_file: Option<String>,
+    session_store: Option<Arc<dyn SessionStore>>,
+    session_account: Option<String>,
+    pool: Option<Arc<AccountPool>>,
+    relogin_lock: tokio::sync::Mutex<()>,
+    session_generation: AtomicU64,
+}
+
+/// Configures the HTTP transport and auth defaults of a [`Scraper`].
+///
+/// When a pre-built client is passed with `with_http_client`, the proxy,
+/// timeout and user agent settings are ignored since they belong to the
+/// `reqwest::Client` itself.
+#[derive(Default)]
+pub struct ScraperBuilder {
+    bearer_token: Option<String>,
+    proxy: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    language: Option<String>,
+    http_client: Option<reqwest::Client>,
+    urls: Option<BaseUrls>,
+    retry_policy: Option<RetryPolicy>,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
+    keep_credentials: bool,
+    cookie_file: Option<String>,
+    session_store: Option<(Arc<dyn SessionStore>, String)>,
+    pool: Option<Arc<AccountPool>>,
+    challenge_handler: Option<Arc<dyn LoginChallengeHandler>>,
+    auth: Option<Box<dyn TwitterAuth + Send + Sync>>,
+}
+
+impl ScraperBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_bearer_token(mut self, bearer_token: impl Into<String>) -> Self {
+        self.bearer_token = Some(bearer_token.into());
+        self
+    }
+
+    /// Routes all requests through an `http://`, `https://` or `socks5://` proxy.
+    pub fn with_proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    pub fn with_base_urls(mut self, urls: BaseUrls) -> Self {
+        self.urls = Some(urls);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Re-logs in with credentials from `provider` when the session expires.
+    pub fn with_credentials_provider(mut self, provider: Arc<dyn CredentialsProvider>) -> Self {
+        self.credentials = Some(provider);
+        self
+    }
+
+    /// Keeps the credentials passed to `Scraper::login` for re-login.
+    pub fn with_keep_credentials(mut self, keep: bool) -> Self {
+        self.keep_credentials = keep;
+        self
+    }
+
+    /// Saves cookies to this file after every automatic re-login.
+    pub fn with_cookie_file(mut self, path: impl Into<String>) -> Self {
+        self.cookie_file = Some(path.into());
+        self
+    }
+
+    /// Loads `account`'s session from `store` on build and saves it back
+    /// after every login or cookie change.
+    pub fn with_session_store(
+        mut self,
+        store: Arc<dyn SessionStore>,
+        account: impl Into<String>,
+    ) -> Self {
+        self.session_store = Some((store, account.into()));
+        self
+    }
+
+    /// Runs read methods through `pool` instead of this scraper's session.
+    pub fn with_account_pool(mut self, pool: Arc<AccountPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    pub fn with_challenge_handler(mut self, handler: Arc<dyn LoginChallengeHandler>) -> Self {
+        self.challenge_handler = Some(handler);
+        self
+    }
+
+    /// Uses `auth` instead of a cookie session, e.g. an `OAuth1Auth` for the
+    /// official API. Login and cookie methods are unavailable with it.
+    pub fn with_auth(mut self, auth: impl TwitterAuth) -> Self {
+        self.auth = Some(Box::new(auth));
+        self
+    }
+
+    pub async fn build(self) -> Result<Scraper> {
+        let bearer_token = self
+            .bearer_token
+            .unwrap_or_else(|| BEARER_TOKEN.to_string());
+        let auth: Box<dyn TwitterAuth + Send + Sync> = match self.auth {
+            Some(auth) => auth,
+            None => {
+                let mut auth = TwitterUserAuth::new(bearer_token).await?;
+                if let Some(language) = self.language {
+                    auth = auth.with_language(language);
+                }
+                if let Some(handler) = self.challenge_handler {
+                    auth = auth.with_challenge_handler(handler);
+                }
+                Box::new(auth)
+            }
+        };
+
+        let http_client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder()
+                    .timeout(self.timeout.unwrap_or(Duration::from_secs(30)));
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+                }
+                builder.build()?
+            }
+        };
+
+        let mut twitter_client = TwitterClient::from_http_client(http_client, auth);
+        if let Some(urls) = self.urls {
+            twitter_client = twitter_client.with_base_urls(urls);
+        }
+        if let Some(retry_policy) = self.retry_policy {
+            twitter_client = twitter_client.with_retry_policy(retry_policy);
+        }
+
+        let mut scraper = Scraper {
+            twitter_client,
+            credentials: self.credentials,
+            keep_credentials: self.keep_credentials,
+            cookie_file: self.cookie_file,
+            session_store: None,
+            session_account: None,
+            pool: self.pool,
+            relogin_lock: tokio::sync::Mutex::new(()),
+            session_generation: AtomicU64::new(0),
+        };
+        if let Some((store, account)) = self.session_store {
+            scraper.set_session_store(store, account);
+            scraper.load_session().await?;
+        }
+        Ok(scraper)
+    }
 }
 
 impl Scraper {
     pub async fn new() -> Result<Self> {
-        let auth = Box::new(TwitterUserAuth::new(BEARER_TOKEN.to_string()).await?);
-        let twitter_client = TwitterClient::new(auth.clone())?;
-        Ok(Self { twitter_client })
+        ScraperBuilder::new().build().await
+    }
+
+    pub fn builder() -> ScraperBuilder {
+        ScraperBuilder::new()
+    }
+
+    pub fn set_base_urls(&mut self, urls: BaseUrls) {
+        self.twitter_client.urls = urls;
+    }
+
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.twitter_client.retry_policy = retry_policy;
+    }
+
+    pub fn set_credentials_provider(&mut self, provider: Arc<dyn CredentialsProvider>) {
+        self.credentials = Some(provider);
+    }
+
+    pub fn set_keep_credentials(&mut self, keep: bool) {
+        self.keep_credentials = keep;
+    }
+
+    pub fn set_cookie_file(&mut self, path: impl Into<String>) {
+        self.cookie_file = Some(path.into());
+    }
+
+    pub fn set_session_store(&mut self, store: Arc<dyn SessionStore>, account: impl Into<String>) {
+        self.session_store = Some(store);
+        self.session_account = Some(account.into());
+    }
+
+    /// Restores the stored session of the configured account, if there is
+    /// one. Returns whether a session was found.
+    pub async fn load_session(&mut self) -> Result<bool> {
+        let (Some(store), Some(account)) = (&self.session_store, &self.session_account) else {
+            return Ok(false);
+        };
+        let Some(session) = store.load(account).await? else {
+            return Ok(false);
+        };
+        self.user_auth()?.restore_session(session).await;
+        Ok(true)
+    }
+
+    async fn persist_session(&self) -> Result<()> {
+        let (Some(store), Some(account)) = (&self.session_store, &self.session_account) else {
+            return Ok(());
+        };
+        let session = self.user_auth()?.session_snapshot().await;
+        store.save(account, &session).await
+    }
+
+    pub fn set_account_pool(&mut self, pool: Arc<AccountPool>) {
+        self.pool = Some(pool);
+    }
+
+    pub fn account_pool(&self) -> Option<&AccountPool> {
+        self.pool.as_deref()
+    }
+
+    pub fn set_challenge_handler(&mut self, handler: Arc<dyn LoginChallengeHandler>) -> Result<()> {
+        let auth = self.user_auth()?.clone().with_challenge_handler(handler);
+        self.twitter_client.auth = Box::new(auth);
+        Ok(())
+    }
+
+    pub fn rate_limits(&self) -> &RateLimits {
+        &self.twitter_client.rate_limits
+    }
+
+    pub fn rate_limit_status(&self, endpoint: &str) -> Option<RateLimitStatus> {
+        self.twitter_client.rate_limits.get(endpoint)
     }
 
     pub async fn login(
@@ -37,7 +320,7 @@ impl Scraper {
         {
             let mut auth = user_auth.clone();
             auth.login(
-                &self.twitter_client.client,
+                &self.twitter_client,
                 &username,
                 &password,
                 email.as_deref(),
@@ -45,16 +328,180 @@ impl Scraper {
             )
             .await?;
 
-            self.twitter_client.auth = Box::new(auth.clone());
-            //self.client = TwitterClient::new(Box::new(auth))?;
+            self.twitter_client.auth = Box::new(auth);
+            if self.session_store.is_some() && self.session_account.is_none() {
+                self.session_account = Some(username.clone());
+            }
+            self.persist_session().await?;
+            if self.keep_credentials {
+                let credentials = LoginCredentials {
+                    username,
+                    password,
+                    email,
+                    two_factor_secret,
+                };
+                self.credentials = Some(Arc::new(credentials));
+            }
             Ok(())
         } else {
             Err(TwitterError::Auth("Invalid auth type".into()))
         }
     }
 
+    fn user_auth(&self) -> Result<&TwitterUserAuth> {
+        self.twitter_client
+            .auth
+            .as_any()
+            .downcast_ref::<TwitterUserAuth>()
+            .ok_or_else(|| TwitterError::Auth("Invalid auth type".into()))
+    }
+
+    /// Logs out on the server and resets this scraper to a guest session:
+    /// cookies, kept credentials and the persisted session are removed.
+    pub async fn logout(&mut self) -> Result<()> {
+        // Local state is reset even when the server call fails; the error is
+        // still returned so callers know the server session may be alive.
+        let result = self.user_auth()?.logout(&self.twitter_client).await;
+
+        // Every cleanup step runs; the first failure is reported after the
+        // server's.
+        let mut cleanup = Ok(());
+        self.credentials = None;
+        let account = self.session_account.take();
+        if let Some(cookie_file) = &self.cookie_file {
+            match tokio::fs::remove_file(cookie_file).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => cleanup = Err(e.into()),
+                _ => {}
+            }
+        }
+        if let (Some(store), Some(account)) = (&self.session_store, account) {
+            if let Err(e) = store.delete(&account).await {
+                cleanup = cleanup.and(Err(e));
+            }
+        }
+        self.session_generation.fetch_add(1, Ordering::SeqCst);
+        result.and(cleanup)
+    }
+
+    /// Starts a login that can be paused at challenges; see [`LoginFlow`].
+    pub async fn begin_login(&self) -> Result<LoginFlow> {
+        self.user_auth()?.begin_login(&self.twitter_client).await
+    }
+
+    /// Loads the session state of a `LoginFlow` saved by another process.
+    pub async fn resume_login(&self, flow: &LoginFlow) -> Result<()> {
+        self.user_auth()?.resume_login(flow).await
+    }
+
+    pub async fn continue_login(
+        &self,
+        flow: &mut LoginFlow,
+        credentials: &LoginCredentials,
+    ) -> Result<()> {
+        self.user_auth()?
+            .continue_login(&self.twitter_client, flow, credentials)
+            .await?;
+        if flow.is_complete() {
+            self.persist_session().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn answer_login_challenge(
+        &self,
+        flow: &mut LoginFlow,
+        response: ChallengeResponse,
+    ) -> Result<()> {
+        self.user_auth()?
+            .answer_login_challenge(&self.twitter_client, flow, response)
+            .await?;
+        if flow.is_complete() {
+            self.persist_session().await?;
+        }
+        Ok(())
+    }
+
+    /// Runs `op`, and if it fails because the session expired, logs in again
+    /// with the configured credentials and retries it once.
+    async fn with_session<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let generation = self.session_generation.load(Ordering::SeqCst);
+        let cookies_version = self.cookies_version();
+        let result = match op().await {
+            Err(err) if self.credentials.is_some() && err.is_auth_failure() => {
+                tracing::info!(error = %err, "Session rejected, logging in again");
+                self.relogin(generation).await?;
+                op().await
+            }
+            result => result,
+        };
+
+        // Responses may have rotated cookies such as ct0.
+        if self.cookies_version() != cookies_version {
+            if let Err(err) = self.persist_session().await {
+                tracing::warn!(error = %err, "Failed to save rotated session cookies");
+            }
+        }
+        result
+    }
+
+    /// Runs a read-only `op` on the account pool when one is configured,
+    /// otherwise on this scraper's own session.
+    async fn read<'a, T, F, Fut>(&'a self, op: F) -> Result<T>
+    where
+        F: Fn(&'a TwitterClient) -> Fut,
+        Fut: Future<Output = Result<T>> + 'a,
+    {
+        match &self.pool {
+            Some(pool) => pool.run(op).await,
+            None => self.with_session(|| op(&self.twitter_client)).await,
+        }
+    }
+
+    fn cookies_version(&self) -> u64 {
+        self.user_auth()
+            .map(|auth| auth.cookies_version())
+            .unwrap_or_default()
+    }
+
+    async fn relogin(&self, failed_generation: u64) -> Result<()> {
+        let _guard = self.relogin_lock.lock().await;
+        // Another request already refreshed the session while we waited.
+        if self.session_generation.load(Ordering::SeqCst) != failed_generation {
+            return Ok(());
+        }
+
+        let provider = self
+            .credentials
+            .as_ref()
+            .ok_or_else(|| TwitterError::Auth("No credentials for re-login".into()))?;
+        let credentials = provider.credentials().await?;
+
+        // Clones share the cookie jar, so logging in on a copy refreshes the
+        // session used by every later request.
+        let mut auth = self.user_auth()?.clone();
+        auth.login(
+            &self.twitter_client,
+            &credentials.username,
+            &credentials.password,
+            credentials.email.as_deref(),
+            credentials.two_factor_secret.as_deref(),
+        )
+        .await?;
+        self.session_generation.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(cookie_file) = &self.cookie_file {
+            auth.save_cookies_to_file(cookie_file).await?;
+        }
+        self.persist_session().await
+    }
+
     pub async fn get_profile(&self, username: &str) -> Result<crate::models::Profile> {
-        crate::profile::get_profile(&self.twitter_client, username).await
+        self.read(|client| crate::profile::get_profile(client, username))
+            .await
     }
     pub async fn send_tweet(
         &self,
@@ -62,16 +509,140 @@ impl Scraper {
         reply_to: Option<&str>,
         media_data: Option<Vec<(Vec<u8>, String)>>,
     ) -> Result<Value> {
-        crate::tweets::create_tweet_request(&self.twitter_client, text, reply_to, media_data).await
+        self.with_session(|| {
+            crate::tweets::create_tweet_request(
+                &self.twitter_client,
+                text,
+                reply_to,
+                media_data.clone(),
+            )
+        })
+        .await
+    }
+
+    pub async fn send_poll_tweet(
+        &self,
+        text: &str,
+        reply_to: Option<&str>,
+        poll: &NewPoll,
+    ) -> Result<Value> {
+        self.with_session(|| {
+            crate::tweets::create_poll_tweet(&self.twitter_client, text, reply_to, poll)
+        })
+        .await
+    }
+
+    /// Returns the poll on `tweet_id` with its current vote counts.
+    pub async fn get_poll(&self, tweet_id: &str) -> Result<Option<PollV2>> {
+        self.read(|client| crate::polls::fetch_poll(client, tweet_id))
+            .await
+    }
+
+    pub async fn send_thread(&self, parts: Vec<ThreadPart>) -> Result<Vec<String>> {
+        self.send_thread_with_options(parts, ThreadOptions::default())
+            .await
+    }
+
+    /// Posts `parts` as a chain of replies and returns their IDs in order.
+    ///
+    /// Every part is validated before the first one is posted. Each part is
+    /// then sent on its own, so a re-login retry never reposts parts that
+    /// already went out. If a part fails, the error is a
+    /// `TwitterError::Thread` listing the IDs that were posted.
+    pub async fn send_thread_with_options(
+        &self,
+        parts: Vec<ThreadPart>,
+        options: ThreadOptions,
+    ) -> Result<Vec<String>> {
+        for (index, part) in parts.iter().enumerate() {
+            let has_media = part.media.as_ref().is_some_and(|media| !media.is_empty());
+            if let Err(err) = crate::text::validate_tweet(&part.text, has_media) {
+                return Err(TwitterError::Thread {
+                    posted: Vec::new(),
+                    failed_part: index,
+                    rolled_back: false,
+                    source: Box::new(err),
+                });
+            }
+        }
+
+        let mut posted: Vec<String> = Vec::with_capacity(parts.len());
+
+        for (index, part) in parts.into_iter().enumerate() {
+            let reply_to = posted.last().cloned().or_else(|| options.reply_to.clone());
+            let result = self
+                .with_session(|| {
+                    crate::tweets::create_tweet_request(
+                        &self.twitter_client,
+                        &part.text,
+                        reply_to.as_deref(),
+                        part.media.clone(),
+                    )
+                })
+                .await
+                .and_then(|value| crate::tweets::created_tweet_id(&value));
+
+            match result {
+                Ok(id) => posted.push(id),
+                Err(err) => {
+                    let posted = if options.rollback_on_failure {
+                        self.delete_posted(posted).await
+                    } else {
+                        posted
+                    };
+                    return Err(TwitterError::Thread {
+                        rolled_back: options.rollback_on_failure && posted.is_empty(),
+                        posted,
+                        failed_part: index,
+                        source: Box::new(err),
+                    });
+                }
+            }
+        }
+
+        Ok(posted)
+    }
+
+    /// Splits `text` with `split_text` and posts the parts as a thread.
+    pub async fn send_text_as_thread(
+        &self,
+        text: &str,
+        split: &SplitOptions,
+        options: ThreadOptions,
+    ) -> Result<Vec<String>> {
+        let parts = split_text(text, split)
+            .into_iter()
+            .map(ThreadPart::new)
+            .collect();
+        self.send_thread_with_options(parts, options).await
+    }
+
+    /// Deletes a partial thread, last part first. Returns the IDs that could
+    /// not be deleted.
+    async fn delete_posted(&self, posted: Vec<String>) -> Vec<String> {
+        let mut remaining = Vec::new();
+        for id in posted.into_iter().rev() {
+            if let Err(err) = self.delete_tweet(&id).await {
+                tracing::warn!(tweet_id = %id, error = %err, "Failed to roll back thread part");
+                remaining.insert(0, id);
+            }
+        }
+        remaining
     }
 
     pub async fn get_home_timeline(
         &self,
         count: i32,
         seen_tweet_ids: Vec<String>,
-    ) -> Result<Vec<TwitterResponse>> {
-        crate::timeline::home::fetch_home_timeline(&self.twitter_client, count, seen_tweet_ids)
-            .await
+    ) -> Result<HomeTimelinePage> {
+        self.with_session(|| {
+            crate::timeline::home::fetch_home_timeline(
+                &self.twitter_client,
+                count,
+                seen_tweet_ids.clone(),
+            )
+        })
+        .await
     }
 
     pub async fn save_cookies(&self, cookie_file: &str) -> Result<()> {
@@ -87,6 +658,35 @@ impl Scraper {
         }
     }
 
+    pub async fn load_cookies(&mut self, cookie_file: &str) -> Result<()> {
+        let mut auth = self.user_auth()?.clone();
+        auth.load_cookies_from_file(cookie_file).await?;
+        self.twitter_client.auth = Box::new(auth);
+        self.persist_session().await
+    }
+
+    pub async fn import_netscape_cookies(&mut self, text: &str) -> Result<()> {
+        let mut auth = self.user_auth()?.clone();
+        auth.import_netscape_cookies(text).await?;
+        self.twitter_client.auth = Box::new(auth);
+        self.persist_session().await
+    }
+
+    pub async fn export_netscape_cookies(&self) -> Result<String> {
+        self.user_auth()?.export_netscape_cookies().await
+    }
+
+    pub async fn import_browser_cookies(&mut self, json: &str) -> Result<()> {
+        let mut auth = self.user_auth()?.clone();
+        auth.import_browser_cookies(json).await?;
+        self.twitter_client.auth = Box::new(auth);
+        self.persist_session().await
+    }
+
+    pub async fn export_browser_cookies(&self) -> Result<String> {
+        self.user_auth()?.export_browser_cookies().await
+    }
+
     pub async fn get_cookie_string(&self) -> Result<String> {
         if let Some(user_auth) = self
             .twitter_client
@@ -110,9 +710,8 @@ impl Scraper {
             let mut auth = user_auth.clone();
             auth.set_cookies(json_str).await?;
 
-            self.twitter_client.auth = Box::new(auth.clone());
-            self.twitter_client = TwitterClient::new(Box::new(auth))?;
-            Ok(())
+            self.twitter_client.auth = Box::new(auth);
+            self.persist_session().await
         } else {
             Err(TwitterError::Auth("Invalid auth type".into()))
         }
@@ -128,9 +727,8 @@ impl Scraper {
             let mut auth = user_auth.clone();
             auth.set_from_cookie_string(cookie_string).await?;
 
-            self.twitter_client.auth = Box::new(auth.clone());
-            self.twitter_client = TwitterClient::new(Box::new(auth))?;
-            Ok(())
+            self.twitter_client.auth = Box::new(auth);
+            self.persist_session().await
         } else {
             Err(TwitterError::Auth("Invalid auth type".into()))
         }
@@ -142,7 +740,10 @@ impl Scraper {
         count: i32,
         cursor: Option<String>,
     ) -> Result<(Vec<Profile>, Option<String>)> {
-        crate::relationships::get_followers(&self.twitter_client, user_id, count, cursor).await
+        self.read(|client| {
+            crate::relationships::get_followers(client, user_id, count, cursor.clone())
+        })
+        .await
     }
 
     pub async fn get_following(
@@ -151,15 +752,20 @@ impl Scraper {
         count: i32,
         cursor: Option<String>,
     ) -> Result<(Vec<Profile>, Option<String>)> {
-        crate::relationships::get_following(&self.twitter_client, user_id, count, cursor).await
+        self.read(|client| {
+            crate::relationships::get_following(client, user_id, count, cursor.clone())
+        })
+        .await
     }
 
     pub async fn follow_user(&self, username: &str) -> Result<()> {
-        crate::relationships::follow_user(&self.twitter_client, username).await
+        self.with_session(|| crate::relationships::follow_user(&self.twitter_client, username))
+            .await
     }
 
     pub async fn unfollow_user(&self, username: &str) -> Result<()> {
-        crate::relationships::unfollow_user(&self.twitter_client, username).await
+        self.with_session(|| crate::relationships::unfollow_user(&self.twitter_client, username))
+            .await
     }
 
     pub async fn send_quote_tweet(
@@ -168,8 +774,15 @@ impl Scraper {
         quoted_tweet_id: &str,
         media_data: Option<Vec<(Vec<u8>, String)>>,
     ) -> Result<Value> {
-        crate::tweets::create_quote_tweet(&self.twitter_client, text, quoted_tweet_id, media_data)
-            .await
+        self.with_session(|| {
+            crate::tweets::create_quote_tweet(
+                &self.twitter_client,
+                text,
+                quoted_tweet_id,
+                media_data.clone(),
+            )
+        })
+        .await
     }
 
     pub async fn fetch_tweets_and_replies(
@@ -178,8 +791,10 @@ impl Scraper {
         max_tweets: i32,
         cursor: Option<&str>,
     ) -> Result<V2QueryTweetsResponse> {
-        crate::tweets::fetch_tweets_and_replies(&self.twitter_client, username, max_tweets, cursor)
-            .await
+        self.read(|client| {
+            crate::tweets::fetch_tweets_and_replies(client, username, max_tweets, cursor)
+        })
+        .await
     }
     pub async fn fetch_tweets_and_replies_by_user_id(
         &self,
@@ -187,12 +802,9 @@ impl Scraper {
         max_tweets: i32,
         cursor: Option<&str>,
     ) -> Result<V2QueryTweetsResponse> {
-        crate::tweets::fetch_tweets_and_replies_by_user_id(
-            &self.twitter_client,
-            user_id,
-            max_tweets,
-            cursor,
-        )
+        self.read(|client| {
+            crate::tweets::fetch_tweets_and_replies_by_user_id(client, user_id, max_tweets, cursor)
+        })
         .await
     }
     pub async fn fetch_list_tweets(
@@ -201,15 +813,124 @@ impl Scraper {
         max_tweets: i32,
         cursor: Option<&str>,
     ) -> Result<Value> {
-        crate::tweets::fetch_list_tweets(&self.twitter_client, list_id, max_tweets, cursor).await
+        self.read(|client| crate::tweets::fetch_list_tweets(client, list_id, max_tweets, cursor))
+            .await
     }
 
     pub async fn like_tweet(&self, tweet_id: &str) -> Result<Value> {
-        crate::tweets::like_tweet(&self.twitter_client, tweet_id).await
+        self.with_session(|| crate::tweets::like_tweet(&self.twitter_client, tweet_id))
+            .await
     }
 
     pub async fn retweet(&self, tweet_id: &str) -> Result<Value> {
-        crate::tweets::retweet(&self.twitter_client, tweet_id).await
+        self.with_session(|| crate::tweets::retweet(&self.twitter_client, tweet_id))
+            .await
+    }
+
+    pub async fn delete_tweet(&self, tweet_id: &str) -> Result<()> {
+        self.with_session(|| crate::tweets::delete_tweet(&self.twitter_client, tweet_id))
+            .await
+    }
+
+    pub async fn unlike_tweet(&self, tweet_id: &str) -> Result<()> {
+        self.with_session(|| crate::tweets::unlike_tweet(&self.twitter_client, tweet_id))
+            .await
+    }
+
+    pub async fn unretweet(&self, tweet_id: &str) -> Result<()> {
+        self.with_session(|| crate::tweets::unretweet(&self.twitter_client, tweet_id))
+            .await
+    }
+
+    /// Uploads media and returns its ID for use in scheduled tweets and
+    /// drafts.
+    pub async fn upload_media(&self, data: Vec<u8>, media_type: &str) -> Result<String> {
+        self.with_session(|| {
+            crate::tweets::upload_media(&self.twitter_client, data.clone(), media_type)
+        })
+        .await
+    }
+
+    /// Queues a tweet to be posted at `execute_at` and returns the scheduled
+    /// tweet's ID. `media_ids` come from `upload_media`.
+    pub async fn schedule_tweet(
+        &self,
+        text: &str,
+        media_ids: &[String],
+        execute_at: DateTime<Utc>,
+    ) -> Result<String> {
+        self.with_session(|| {
+            crate::scheduled::create_scheduled_tweet(
+                &self.twitter_client,
+                text,
+                media_ids,
+                execute_at,
+            )
+        })
+        .await
+    }
+
+    pub async fn get_scheduled_tweets(&self) -> Result<Vec<ScheduledTweet>> {
+        self.with_session(|| crate::scheduled::fetch_scheduled_tweets(&self.twitter_client))
+            .await
+    }
+
+    pub async fn edit_scheduled_tweet(
+        &self,
+        scheduled_tweet_id: &str,
+        text: &str,
+        media_ids: &[String],
+        execute_at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.with_session(|| {
+            crate::scheduled::edit_scheduled_tweet(
+                &self.twitter_client,
+                scheduled_tweet_id,
+                text,
+                media_ids,
+                execute_at,
+            )
+        })
+        .await
+    }
+
+    pub async fn cancel_scheduled_tweet(&self, scheduled_tweet_id: &str) -> Result<()> {
+        self.with_session(|| {
+            crate::scheduled::delete_scheduled_tweet(&self.twitter_client, scheduled_tweet_id)
+        })
+        .await
+    }
+
+    /// Saves a draft and returns its ID.
+    pub async fn create_draft(&self, text: &str, media_ids: &[String]) -> Result<String> {
+        self.with_session(|| {
+            crate::scheduled::create_draft_tweet(&self.twitter_client, text, media_ids)
+        })
+        .await
+    }
+
+    pub async fn get_drafts(&self) -> Result<Vec<DraftTweet>> {
+        self.with_session(|| crate::scheduled::fetch_draft_tweets(&self.twitter_client))
+            .await
+    }
+
+    pub async fn edit_draft(
+        &self,
+        draft_tweet_id: &str,
+        text: &str,
+        media_ids: &[String],
+    ) -> Result<()> {
+        self.with_session(|| {
+            crate::scheduled::edit_draft_tweet(&self.twitter_client, draft_tweet_id, text, media_ids)
+        })
+        .await
+    }
+
+    pub async fn delete_draft(&self, draft_tweet_id: &str) -> Result<()> {
+        self.with_session(|| {
+            crate::scheduled::delete_draft_tweet(&self.twitter_client, draft_tweet_id)
+        })
+        .await
     }
 
     pub async fn create_long_tweet(
@@ -218,11 +939,20 @@ impl Scraper {
         reply_to: Option<&str>,
         media_ids: Option<Vec<String>>,
     ) -> Result<Value> {
-        crate::tweets::create_long_tweet(&self.twitter_client, text, reply_to, media_ids).await
+        self.with_session(|| {
+            crate::tweets::create_long_tweet(
+                &self.twitter_client,
+                text,
+                reply_to,
+                media_ids.clone(),
+            )
+        })
+        .await
     }
 
-    pub async fn get_tweet(&self, id: &str) -> Result<Tweet> {
-        crate::tweets::get_tweet(&self.twitter_client, id).await
+    pub async fn get_tweet(&self, id: &str) -> Result<TweetDetail> {
+        self.read(|client| crate::tweets::get_tweet(client, id))
+            .await
     }
 
     pub async fn search_tweets(
@@ -232,7 +962,10 @@ impl Scraper {
         search_mode: SearchMode,
         cursor: Option<String>,
     ) -> Result<QueryTweetsResponse> {
-        fetch_search_tweets(&self.twitter_client, query, max_tweets, search_mode, cursor).await
+        self.read(|client| {
+            fetch_search_tweets(client, query, max_tweets, search_mode, cursor.clone())
+        })
+        .await
     }
 
     pub async fn search_profiles(
@@ -241,7 +974,10 @@ impl Scraper {
         max_profiles: i32,
         cursor: Option<String>,
     ) -> Result<QueryProfilesResponse> {
-        crate::search::search_profiles(&self.twitter_client, query, max_profiles, cursor).await
+        self.read(|client| {
+            crate::search::search_profiles(client, query, max_profiles, cursor.clone())
+        })
+        .await
     }
 
     pub async fn get_user_tweets(
@@ -250,8 +986,10 @@ impl Scraper {
         count: i32,
         cursor: Option<String>,
     ) -> Result<V2QueryTweetsResponse> {
-        crate::tweets::fetch_user_tweets(&self.twitter_client, user_id, count, cursor.as_deref())
-            .await
+        self.read(|client| {
+            crate::tweets::fetch_user_tweets(client, user_id, count, cursor.as_deref())
+        })
+        .await
     }
 
     pub async fn get_direct_message_conversations(
@@ -259,11 +997,191 @@ impl Scraper {
         screen_name: &str,
         cursor: Option<&str>,
     ) -> Result<DirectMessagesResponse> {
-        crate::messages::get_direct_message_conversations(&self.twitter_client, screen_name, cursor)
-            .await
+        self.with_session(|| {
+            crate::messages::get_direct_message_conversations(
+                &self.twitter_client,
+                screen_name,
+                cursor,
+            )
+        })
+        .await
     }
 
     pub async fn send_direct_message(&self, conversation_id: &str, text: &str) -> Result<Value> {
-        crate::messages::send_direct_message(&self.twitter_client, conversation_id, text).await
+        self.with_session(|| {
+            crate::messages::send_direct_message(&self.twitter_client, conversation_id, text)
+        })
+        .await
+    }
+
+    pub fn search_tweets_stream<'a>(
+        &'a self,
+        query: &'a str,
+        search_mode: SearchMode,
+        max_tweets: usize,
+    ) -> impl Stream<Item = Result<Tweet>> + 'a {
+        paginate(max_tweets, move |cursor| async move {
+            let response = self
+                .read(|client| fetch_search_tweets(client, query, 50, search_mode, cursor.clone()))
+                .await?;
+            Ok(Page {
+                items: response.tweets,
+                next: response.next,
+            })
+        })
+    }
+
+    pub fn search_profiles_stream<'a>(
+        &'a self,
+        query: &'a str,
+        max_profiles: usize,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate(max_profiles, move |cursor| async move {
+            let response = self
+                .read(|client| crate::search::search_profiles(client, query, 50, cursor.clone()))
+                .await?;
+            Ok(Page {
+                items: response.profiles,
+                next: response.next,
+            })
+        })
+    }
+
+    pub fn followers_stream<'a>(
+        &'a self,
+        user_id: &'a str,
+        max_profiles: usize,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate(max_profiles, move |cursor| async move {
+            let (profiles, next) = self
+                .read(|client| {
+                    crate::relationships::get_followers(client, user_id, 50, cursor.clone())
+                })
+                .await?;
+            Ok(Page {
+                items: profiles,
+                next,
+            })
+        })
+    }
+
+    pub fn following_stream<'a>(
+        &'a self,
+        user_id: &'a str,
+        max_profiles: usize,
+    ) -> impl Stream<Item = Result<Profile>> + 'a {
+        paginate(max_profiles, move |cursor| async move {
+            let (profiles, next) = self
+                .read(|client| {
+                    crate::relationships::get_following(client, user_id, 50, cursor.clone())
+                })
+                .await?;
+            Ok(Page {
+                items: profiles,
+                next,
+            })
+        })
+    }
+
+    pub fn user_tweets_stream<'a>(
+        &'a self,
+        user_id: &'a str,
+        max_tweets: usize,
+    ) -> impl Stream<Item = Result<Tweet>> + 'a {
+        paginate(max_tweets, move |cursor| async move {
+            let response = self
+                .read(|client| {
+                    crate::tweets::fetch_user_tweets(client, user_id, 40, cursor.as_deref())
+                })
+                .await?;
+            Ok(Page {
+                items: response.tweets,
+                next: response.next,
+            })
+        })
+    }
+
+    pub fn tweets_and_replies_stream<'a>(
+        &'a self,
+        username: &'a str,
+        max_tweets: usize,
+    ) -> impl Stream<Item = Result<Tweet>> + 'a {
+        paginate(max_tweets, move |cursor| async move {
+            let response = self
+                .read(|client| {
+                    crate::tweets::fetch_tweets_and_replies(client, username, 40, cursor.as_deref())
+                })
+                .await?;
+            Ok(Page {
+                items: response.tweets,
+                next: response.next,
+            })
+        })
+    }
+
+    pub fn list_tweets_stream<'a>(
+        &'a self,
+        list_id: &'a str,
+        max_tweets: usize,
+    ) -> impl Stream<Item = Result<Tweet>> + 'a {
+        paginate(max_tweets, move |cursor| async move {
+            let response = self
+                .read(|client| {
+                    crate::tweets::fetch_list_timeline(client, list_id, 40, cursor.as_deref())
+                })
+                .await?;
+            Ok(Page {
+                items: response.tweets,
+                next: response.next,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::cookies::{CookieFile, StoredCookie, COOKIE_FILE_VERSION};
+    use crate::auth::session_store::MemorySessionStore;
+
+    fn session() -> CookieFile {
+        let cookie = |name: &str| StoredCookie {
+            name: name.to_string(),
+            value: "secret".to_string(),
+            domain: Some(".x.com".to_string()),
+            path: Some("/".to_string()),
+            expires: None,
+            secure: true,
+            http_only: true,
+            same_site: None,
+        };
+        CookieFile {
+            version: COOKIE_FILE_VERSION,
+            cookies: vec![cookie("auth_token"), cookie("ct0")],
+        }
+    }
+
+    #[tokio::test]
+    async fn logout_deletes_stored_session_when_cleanup_fails() {
+        let store = Arc::new(MemorySessionStore::new());
+        store.save("alice", &session()).await.unwrap();
+
+        // Nothing listens on the origin, so the server logout fails too, and
+        // a directory can't be removed with `remove_file`.
+        let mut scraper = Scraper::builder()
+            .with_base_urls(BaseUrls::from_origin("http://127.0.0.1:9"))
+            .with_retry_policy(RetryPolicy::none())
+            .with_cookie_file(std::env::temp_dir().to_string_lossy())
+            .with_session_store(store.clone(), "alice")
+            .build()
+            .await
+            .unwrap();
+        assert!(scraper.user_auth().unwrap().has_session().await);
+
+        assert!(scraper.logout().await.is_err());
+        assert!(store.load("alice").await.unwrap().is_none());
+        assert!(!scraper.user_auth().unwrap().has_session().await);
+        assert_eq!(scraper.session_generation.load(Ordering::SeqCst), 1);
+        assert!(scraper.session_account.is_none());
     }
 }
@@ -1,6 +1,6 @@
 use crate::api::client::TwitterClient;
 use crate::api::requests::request_api;
-use crate::error::Result;
+use crate::error::{check_partial_errors, Result};
 use crate::timeline::search::{
     parse_search_timeline_tweets, parse_search_timeline_users, SearchTimeline,
 };
@@ -24,8 +24,10 @@ pub async fn fetch_search_tweets(
     cursor: Option<String>,
 ) -> Result<QueryTweetsResponse> {
     let timeline = get_search_timeline(client, query, max_tweets, search_mode, cursor).await?;
+    let response = parse_search_timeline_tweets(&timeline);
+    check_partial_errors(&response.warnings, response.tweets.is_empty())?;
 
-    Ok(parse_search_timeline_tweets(&timeline))
+    Ok(response)
 }
 
 pub async fn search_profiles(
@@ -36,8 +38,10 @@ pub async fn search_profiles(
 ) -> Result<QueryProfilesResponse> {
     let timeline =
         get_search_timeline(client, query, max_profiles, SearchMode::Users, cursor).await?;
+    let response = parse_search_timeline_users(&timeline);
+    check_partial_errors(&response.warnings, response.profiles.is_empty())?;
 
-    Ok(parse_search_timeline_users(&timeline))
+    Ok(response)
 }
 
 async fn get_search_timeline(
@@ -133,15 +137,16 @@ async fn get_search_timeline(
         .join("&");
 
     let mut headers = reqwest::header::HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let url = format!(
-        "https://api.twitter.com/graphql/gkjsKepM6gl_HmFWoWKfgg/SearchTimeline?{}",
+        "{}?{}",
+        client.urls.graphql_url("/gkjsKepM6gl_HmFWoWKfgg/SearchTimeline"),
         query_string
     );
 
     let (response, _) =
-        request_api::<SearchTimeline>(&client.client, &url, headers, Method::GET, None).await?;
+        request_api::<SearchTimeline>(client, &url, headers, Method::GET, None).await?;
 
     Ok(response)
 }
@@ -0,0 +1,396 @@
+//! Tweet length counting compatible with twitter-text (config v3).
+//!
+//! Text is NFC-normalized first. URLs count as 23 characters whatever their
+//! length, emoji count as 2, and so does every code point outside the Latin,
+//! general punctuation and other ranges Twitter weighs as 1.
+
+use crate::error::{Result, TwitterError};
+use lazy_static::lazy_static;
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const MAX_TWEET_LENGTH: usize = 280;
+/// Limit for long-form note tweets sent with `create_long_tweet`.
+pub const MAX_LONG_TWEET_LENGTH: usize = 25_000;
+pub const URL_LENGTH: usize = 23;
+
+const SCALE: usize = 100;
+const DEFAULT_WEIGHT: usize = 200;
+const LIGHT_WEIGHT: usize = 100;
+/// Code point ranges weighted 100 instead of 200.
+const LIGHT_RANGES: &[(u32, u32)] = &[(0, 4351), (8192, 8205), (8208, 8223), (8242, 8247)];
+
+/// Generic TLDs recognised in bare domains such as `example.com`.
+const GENERIC_TLDS: &str = "\
+    aero agency amazon app apple art asia bank biz blog blue book business cafe capital \
+    care cat center chat city click cloud club codes com community company consulting coop \
+    design dev digital directory earth education email energy engineering events exchange \
+    expert express finance fun gallery game games global gmbh google gov group guru health \
+    help home host inc info ink int jobs land life link live london ltd market marketing \
+    media microsoft mil mobi money museum music name net network news nyc one online org \
+    page paris photo photos pics pink plus post press pro red run services shop site social \
+    software solutions space store studio systems team tech technology tel tips today tokyo \
+    tools top travel video vip watch website wiki work works world xxx xyz youtube zone";
+
+/// Every country-code TLD, so bare domains like `example.de` count as URLs.
+const COUNTRY_TLDS: &str = "\
+    ac ad ae af ag ai al am ao aq ar as at au aw ax az ba bb bd be bf bg bh bi bj bm bn bo \
+    br bs bt bw by bz ca cc cd cf cg ch ci ck cl cm cn co cr cu cv cw cx cy cz de dj dk dm \
+    do dz ec ee eg er es et eu fi fj fk fm fo fr ga gd ge gf gg gh gi gl gm gn gp gq gr gs \
+    gt gu gw gy hk hm hn hr ht hu id ie il im in io iq ir is it je jm jo jp ke kg kh ki km \
+    kn kp kr kw ky kz la lb lc li lk lr ls lt lu lv ly ma mc md me mg mh mk ml mm mn mo mp \
+    mq mr ms mt mu mv mw mx my mz na nc ne nf ng ni nl no np nr nu nz om pa pe pf pg ph pk \
+    pl pm pn pr ps pt pw py qa re ro rs ru rw sa sb sc sd se sg sh si sk sl sm sn so sr ss \
+    st su sv sx sy sz tc td tf tg th tj tk tl tm tn to tr tt tv tw tz ua ug uk us uy uz va \
+    vc ve vg vi vn vu wf ws ye yt za zm zw";
+
+lazy_static! {
+    static ref RE_URL: Regex = {
+        let tlds = GENERIC_TLDS
+            .split_whitespace()
+            .chain(COUNTRY_TLDS.split_whitespace())
+            .collect::<Vec<_>>()
+            .join("|");
+        Regex::new(&format!(
+            r"(?i)\b(?:https?://|www\.)\S+|\b(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+(?:{})\b(?:/\S*)?",
+            tlds
+        ))
+        .unwrap()
+    };
+}
+
+/// Returns the length Twitter counts for `text`.
+pub fn weighted_length(text: &str) -> usize {
+    let text: String = text.nfc().collect();
+    let mut weight = 0;
+    let mut last = 0;
+    for url in find_urls(&text) {
+        weight += segment_weight(&text[last..url.0]);
+        weight += URL_LENGTH * SCALE;
+        last = url.1;
+    }
+    weight += segment_weight(&text[last..]);
+    weight / SCALE
+}
+
+/// Fails if `text` counts as longer than `max_length`, returning its weighted
+/// length otherwise.
+pub fn validate_length(text: &str, max_length: usize) -> Result<usize> {
+    let length = weighted_length(text);
+    if length > max_length {
+        return Err(TwitterError::InvalidTweet(format!(
+            "Text is {} characters long; the limit is {}",
+            length, max_length
+        )));
+    }
+    Ok(length)
+}
+
+/// Checks text for a regular tweet. Empty text is only allowed alongside
+/// media.
+pub fn validate_tweet(text: &str, has_media: bool) -> Result<usize> {
+    if !has_media && text.trim().is_empty() {
+        return Err(TwitterError::InvalidTweet("Tweet text is empty".into()));
+    }
+    validate_length(text, MAX_TWEET_LENGTH)
+}
+
+#[derive(Debug, Clone)]
+pub struct SplitOptions {
+    pub max_length: usize,
+    /// Appends " 1/n", " 2/n", ... to each part when there is more than one.
+    pub numbering: bool,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            max_length: MAX_TWEET_LENGTH,
+            numbering: false,
+        }
+    }
+}
+
+impl SplitOptions {
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    pub fn with_numbering(mut self, numbering: bool) -> Self {
+        self.numbering = numbering;
+        self
+    }
+}
+
+/// Breaks `text` into parts that each fit in a tweet, preferring sentence
+/// boundaries, then whitespace, and cutting inside a word only when a single
+/// word is too long. URLs are never cut.
+pub fn split_text(text: &str, options: &SplitOptions) -> Vec<String> {
+    let text: String = text.nfc().collect();
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+    if !options.numbering {
+        return pack(&text, options.max_length);
+    }
+
+    // The counter's width depends on the number of parts, so repeat with a
+    // wider reservation until the total fits the width assumed.
+    let mut digits = 1;
+    loop {
+        let budget = options.max_length.saturating_sub(2 + 2 * digits).max(1);
+        let parts = pack(&text, budget);
+        if parts.len() <= 1 {
+            return pack(&text, options.max_length);
+        }
+        if parts.len().to_string().len() <= digits {
+            let total = parts.len();
+            return parts
+                .into_iter()
+                .enumerate()
+                .map(|(index, part)| format!("{} {}/{}", part, index + 1, total))
+                .collect();
+        }
+        digits += 1;
+    }
+}
+
+fn pack(text: &str, budget: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for piece in pieces(text, budget) {
+        let candidate = format!("{}{}", current, piece);
+        if weighted_length(candidate.trim()) <= budget {
+            current = candidate;
+            continue;
+        }
+        if !current.trim().is_empty() {
+            parts.push(current.trim().to_string());
+        }
+        current = piece.trim_start().to_string();
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+/// Splits `text` into the largest units that each fit in `budget`.
+fn pieces(text: &str, budget: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    for sentence in text.split_sentence_bounds() {
+        if weighted_length(sentence.trim()) <= budget {
+            pieces.push(sentence);
+            continue;
+        }
+        for word in sentence.split_inclusive(char::is_whitespace) {
+            if weighted_length(word.trim()) <= budget {
+                pieces.push(word);
+            } else {
+                pieces.extend(word.graphemes(true));
+            }
+        }
+    }
+    pieces
+}
+
+/// Byte ranges of URLs in `text`, without trailing punctuation. A closing
+/// parenthesis is kept when it balances one inside the URL, as in Wikipedia
+/// links.
+fn find_urls(text: &str) -> Vec<(usize, usize)> {
+    RE_URL
+        .find_iter(text)
+        .map(|found| {
+            let mut url = found.as_str();
+            loop {
+                let unbalanced = url.matches(')').count() > url.matches('(').count();
+                match url.chars().last() {
+                    Some('.' | ',' | '!' | '?' | ':' | ';' | '"' | '\'') => {}
+                    Some(')') if unbalanced => {}
+                    _ => break,
+                }
+                url = &url[..url.len() - 1];
+            }
+            (found.start(), found.start() + url.len())
+        })
+        .collect()
+}
+
+fn segment_weight(text: &str) -> usize {
+    text.graphemes(true)
+        .map(|grapheme| {
+            if is_emoji(grapheme) {
+                DEFAULT_WEIGHT
+            } else {
+                grapheme.chars().map(char_weight).sum()
+            }
+        })
+        .sum()
+}
+
+fn char_weight(c: char) -> usize {
+    let code = c as u32;
+    if LIGHT_RANGES
+        .iter()
+        .any(|&(start, end)| (start..=end).contains(&code))
+    {
+        LIGHT_WEIGHT
+    } else {
+        DEFAULT_WEIGHT
+    }
+}
+
+/// An emoji counts as one weighted character however many code points its
+/// sequence (skin tones, ZWJ joins, flags, keycaps) is made of.
+fn is_emoji(grapheme: &str) -> bool {
+    grapheme.chars().any(|c| {
+        matches!(
+            c as u32,
+            0x1F000..=0x1FAFF | 0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x20E3
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cases from twitter-text's conformance/validate.yml (WeightedTweetsCounterTest, v3).
+    #[test]
+    fn counts_latin_as_one() {
+        assert_eq!(weighted_length("This is a test."), 15);
+        assert_eq!(weighted_length(&"a".repeat(280)), 280);
+    }
+
+    #[test]
+    fn counts_urls_as_twenty_three() {
+        assert_eq!(weighted_length("https://twitter.com"), 23);
+        assert_eq!(
+            weighted_length("https://www.example.com/a/very/long/path/that/goes/on/and/on?q=1"),
+            23
+        );
+        assert_eq!(weighted_length("Check out https://t.co/abc."), 10 + 23 + 1);
+        assert_eq!(weighted_length("example.com"), 23);
+        for domain in ["example.de", "foo.uk", "bar.fr", "site.news", "crate.rs"] {
+            assert_eq!(weighted_length(domain), 23, "{}", domain);
+        }
+        assert_eq!(weighted_length("not.adomain"), 11);
+    }
+
+    #[test]
+    fn trims_punctuation_after_urls() {
+        assert_eq!(
+            weighted_length("(see https://example.com/docs)."),
+            5 + 23 + 2
+        );
+        assert_eq!(weighted_length("(example.de)"), 1 + 23 + 1);
+        assert_eq!(
+            weighted_length("https://en.wikipedia.org/wiki/Rust_(programming_language)"),
+            23
+        );
+    }
+
+    #[test]
+    fn counts_cjk_as_two() {
+        assert_eq!(weighted_length("简体中文"), 8);
+        assert_eq!(weighted_length("日本語のテキスト"), 16);
+        assert_eq!(weighted_length("한국어"), 6);
+    }
+
+    #[test]
+    fn counts_emoji_sequences_as_two() {
+        assert_eq!(weighted_length("H🐱☺👨‍👩‍👧‍👦"), 7);
+        // ZWJ sequence with skin tone.
+        assert_eq!(weighted_length("👩🏽‍💻"), 2);
+        // Regional indicator flags.
+        assert_eq!(weighted_length("🇯🇵🇺🇸"), 4);
+        // Keycaps.
+        assert_eq!(weighted_length("1️⃣#️⃣"), 4);
+    }
+
+    #[test]
+    fn normalizes_to_nfc() {
+        // "e" + combining acute accent is a single "é".
+        assert_eq!(weighted_length("cafe\u{301}"), 4);
+        // Conjoining jamo compose into one Hangul syllable.
+        assert_eq!(weighted_length("\u{1100}\u{1161}"), 2);
+    }
+
+    #[test]
+    fn validates_tweet_length() {
+        assert_eq!(validate_tweet(&"a".repeat(280), false).unwrap(), 280);
+        assert!(validate_tweet(&"a".repeat(281), false).is_err());
+        assert!(validate_tweet(&"中".repeat(141), false).is_err());
+        assert!(validate_tweet("  ", false).is_err());
+        assert!(validate_tweet("", true).is_ok());
+    }
+
+    fn assert_numbered(parts: &[String], max_length: usize) {
+        let total = parts.len();
+        for (index, part) in parts.iter().enumerate() {
+            let suffix = format!(" {}/{}", index + 1, total);
+            assert!(part.ends_with(&suffix), "{:?} lacks {:?}", part, suffix);
+            assert!(
+                weighted_length(part) <= max_length,
+                "{:?} is too long",
+                part
+            );
+        }
+    }
+
+    #[test]
+    fn splits_without_numbering() {
+        let text = "word ".repeat(200);
+        let parts = split_text(&text, &SplitOptions::default());
+        assert!(parts.len() > 1);
+        assert!(parts
+            .iter()
+            .all(|part| weighted_length(part) <= MAX_TWEET_LENGTH));
+        assert_eq!(parts.join(" "), text.trim());
+    }
+
+    #[test]
+    fn keeps_short_text_whole_and_unnumbered() {
+        let options = SplitOptions::default().with_numbering(true);
+        assert_eq!(split_text("Hello world.", &options), vec!["Hello world."]);
+        assert!(split_text("   ", &options).is_empty());
+    }
+
+    #[test]
+    fn numbers_parts_across_digit_boundaries() {
+        let options = SplitOptions::default()
+            .with_max_length(20)
+            .with_numbering(true);
+        let mut totals = Vec::new();
+        for words in 1..=400 {
+            let parts = split_text(&"word ".repeat(words), &options);
+            if parts.len() > 1 {
+                assert_numbered(&parts, 20);
+            }
+            totals.push(parts.len());
+        }
+        // Growing the counter from " 9/9" to " 10/10" or " 99/99" to
+        // " 100/100" shrinks every part's budget, so the count may jump past
+        // the boundary, but both sides of it must be reached.
+        for boundary in [10, 100] {
+            assert!(
+                totals.contains(&(boundary - 1)),
+                "never produced {} parts",
+                boundary - 1
+            );
+            assert!(totals.iter().any(|&total| total >= boundary));
+        }
+        assert!(totals.contains(&10));
+    }
+
+    #[test]
+    fn never_cuts_urls() {
+        let url = "https://example.com/some/long/path";
+        let text = format!("{} {}", "word ".repeat(60), url);
+        let parts = split_text(&text, &SplitOptions::default().with_max_length(40));
+        assert!(parts.iter().any(|part| part.contains(url)));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::api::client::TwitterClient;
 use crate::api::requests::request_api;
-use crate::error::Result;
+use crate::error::{check_partial_errors, ApiErrorDetail, Result};
 use reqwest::header::HeaderMap;
 use reqwest::Method;
 use serde::Deserialize;
@@ -12,6 +12,7 @@ use urlencoding;
 #[derive(Debug, Deserialize)]
 pub struct HomeTimelineResponse {
     pub data: Option<HomeData>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -255,11 +256,20 @@ pub struct Views {
     pub state: Option<String>,
 }
 
+/// One page of the home timeline. `warnings` holds GraphQL errors returned
+/// alongside the entries, e.g. for tweets that could not be loaded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HomeTimelinePage {
+    pub tweets: Vec<TwitterResponse>,
+    #[serde(default)]
+    pub warnings: Vec<ApiErrorDetail>,
+}
+
 pub async fn fetch_home_timeline(
     client: &TwitterClient,
     count: i32,
     seen_tweet_ids: Vec<String>,
-) -> Result<Vec<TwitterResponse>> {
+) -> Result<HomeTimelinePage> {
     let variables = serde_json::json!({
         "count": count,
         "includePromotedContent": false,
@@ -294,15 +304,17 @@ pub async fn fetch_home_timeline(
         "responsive_web_enhance_cards_enabled": false,
     });
     let url = format!(
-        "https://x.com/i/api/graphql/HJFjzBgCs16TqxewQOeLNg/HomeTimeline?variables={}&features={}",
+        "{}?variables={}&features={}",
+        client.urls.x_url("/graphql/HJFjzBgCs16TqxewQOeLNg/HomeTimeline"),
         urlencoding::encode(&variables.to_string()),
         urlencoding::encode(&features.to_string())
     );
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
     let (response, _) =
-        request_api::<HomeTimelineResponse>(&client.client, &url, headers, Method::GET, None)
+        request_api::<HomeTimelineResponse>(client, &url, headers, Method::GET, None)
             .await?;
+    let errors = response.errors.unwrap_or_default();
     let home = response
         .data
         .map(|data| data.home.home_timeline.instructions);
@@ -331,5 +343,9 @@ pub async fn fetch_home_timeline(
             }
         }
     }
-    Ok(entries)
+    check_partial_errors(&errors, entries.is_empty())?;
+    Ok(HomeTimelinePage {
+        tweets: entries,
+        warnings: errors,
+    })
 }
@@ -1,3 +1,4 @@
+use crate::error::ApiErrorDetail;
 use crate::profile::parse_profile;
 use crate::timeline::v1::{QueryProfilesResponse, QueryTweetsResponse};
 use crate::timeline::v2::{parse_legacy_tweet, SearchEntryRaw};
@@ -12,6 +13,7 @@ lazy_static! {
 #[derive(Debug, Deserialize)]
 pub struct SearchTimeline {
     pub data: Option<SearchData>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -132,6 +134,7 @@ pub fn parse_search_timeline_tweets(timeline: &SearchTimeline) -> QueryTweetsRes
         tweets,
         next: bottom_cursor,
         previous: top_cursor,
+        warnings: timeline.errors.clone().unwrap_or_default(),
     }
 }
 
@@ -209,5 +212,6 @@ pub fn parse_search_timeline_users(timeline: &SearchTimeline) -> QueryProfilesRe
         profiles,
         next: bottom_cursor,
         previous: top_cursor,
+        warnings: timeline.errors.clone().unwrap_or_default(),
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::ApiErrorDetail;
 use crate::models::tweets::Mention;
 use crate::models::tweets::PlaceRaw;
 use crate::models::{Profile, Tweet};
@@ -310,6 +311,7 @@ pub struct TimelineV1 {
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum ParseTweetResult {
     Success { tweet: Tweet },
     Error { err: String },
@@ -320,6 +322,8 @@ pub struct QueryTweetsResponse {
     pub tweets: Vec<Tweet>,
     pub next: Option<String>,
     pub previous: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<ApiErrorDetail>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -327,6 +331,8 @@ pub struct QueryProfilesResponse {
     pub profiles: Vec<Profile>,
     pub next: Option<String>,
     pub previous: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<ApiErrorDetail>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
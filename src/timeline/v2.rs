@@ -1,4 +1,4 @@
-use crate::error::Result;
+use crate::error::{ApiErrorDetail, Result};
 use crate::error::TwitterError;
 use crate::models::tweets::Mention;
 use crate::models::Tweet;
@@ -92,11 +92,13 @@ pub struct TimelineUserResultRaw {
 pub struct TimelineV2 {
     pub data: Option<TimelineData>,
     pub timeline: Option<TimelineItems>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ThreadedConversation {
     pub data: Option<ThreadedConversationData>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -227,12 +229,10 @@ pub fn parse_legacy_tweet(
         .map(|mentions| {
             mentions
                 .iter()
-                .filter_map(|m| {
-                    Some(Mention {
-                        id: m.id_str.clone().unwrap_or_default(),
-                        name: m.name.clone(),
-                        username: m.screen_name.clone(),
-                    })
+                .map(|m| Mention {
+                    id: m.id_str.clone().unwrap_or_default(),
+                    name: m.name.clone(),
+                    username: m.screen_name.clone(),
                 })
                 .collect()
         })
@@ -417,13 +417,27 @@ pub struct QueryTweetsResponse {
     pub tweets: Vec<Tweet>,
     pub next: Option<String>,
     pub previous: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<ApiErrorDetail>,
 }
 
-pub fn parse_timeline_tweets_v2(timeline: &TimelineV2) -> QueryTweetsResponse {
-    let mut tweets = Vec::new();
-    let mut bottom_cursor = None;
-    let mut top_cursor = None;
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListTimeline {
+    pub data: Option<ListTimelineData>,
+    pub errors: Option<Vec<ApiErrorDetail>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListTimelineData {
+    pub list: Option<ListTimelineList>,
+}
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListTimelineList {
+    pub tweets_timeline: Option<TimelineV2>,
+}
+
+pub fn parse_timeline_tweets_v2(timeline: &TimelineV2) -> QueryTweetsResponse {
     let instructions = timeline
         .data
         .as_ref()
@@ -434,18 +448,39 @@ pub fn parse_timeline_tweets_v2(timeline: &TimelineV2) -> QueryTweetsResponse {
         .and_then(|timeline| timeline.instructions.as_ref())
         .unwrap_or(&EMPTY_INSTRUCTIONS);
 
-    let expected_entry_types = ["tweet-", "profile-conversation-"];
+    let mut response =
+        parse_timeline_instructions(instructions, &["tweet-", "profile-conversation-"]);
+    response.warnings = timeline.errors.clone().unwrap_or_default();
+    response
+}
+
+pub fn parse_list_timeline_tweets(timeline: &ListTimeline) -> QueryTweetsResponse {
+    let instructions = timeline
+        .data
+        .as_ref()
+        .and_then(|data| data.list.as_ref())
+        .and_then(|list| list.tweets_timeline.as_ref())
+        .and_then(|timeline| timeline.timeline.as_ref())
+        .and_then(|timeline| timeline.instructions.as_ref())
+        .unwrap_or(&EMPTY_INSTRUCTIONS);
+
+    let mut response = parse_timeline_instructions(instructions, &["tweet-", "list-conversation-"]);
+    response.warnings = timeline.errors.clone().unwrap_or_default();
+    response
+}
+
+fn parse_timeline_instructions(
+    instructions: &[TimelineInstruction],
+    expected_entry_types: &[&str],
+) -> QueryTweetsResponse {
+    let mut tweets = Vec::new();
+    let mut bottom_cursor = None;
+    let mut top_cursor = None;
 
     for instruction in instructions {
         let entries = instruction
             .entries.as_deref()
-            .unwrap_or_else(|| {
-                instruction
-                    .entry
-                    .as_ref()
-                    .map(std::slice::from_ref)
-                    .unwrap_or_default()
-            });
+            .unwrap_or(instruction.entry.as_slice());
 
         for entry in entries {
             let content = match &entry.content {
@@ -498,6 +533,7 @@ pub fn parse_timeline_tweets_v2(timeline: &TimelineV2) -> QueryTweetsResponse {
         tweets,
         next: bottom_cursor,
         previous: top_cursor,
+        warnings: Vec::new(),
     }
 }
 
@@ -1,11 +1,14 @@
 use crate::api::endpoints::Endpoints;
 use crate::api::requests::{request_api, request_multipart_api};
-use crate::error::{Result, TwitterError};
+use crate::error::{check_partial_errors, ApiErrorDetail, Result, TwitterError};
 use crate::models::tweets::Tweet;
+use crate::polls::{create_poll_card, NewPoll};
 use crate::profile::get_user_id_by_screen_name;
+use crate::timeline::v2::parse_list_timeline_tweets;
 use crate::timeline::v2::parse_threaded_conversation;
 use crate::timeline::v2::parse_timeline_tweets_v2;
 use crate::timeline::v2::QueryTweetsResponse;
+use crate::timeline::v2::ListTimeline;
 use crate::timeline::v2::ThreadedConversation;
 use reqwest::header::HeaderMap;
 use reqwest::Method;
@@ -46,7 +49,7 @@ pub async fn fetch_tweets(
     cursor: Option<&str>,
 ) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let mut variables = json!({
         "userId": user_id,
@@ -59,8 +62,8 @@ pub async fn fetch_tweets(
     }
 
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/YNXM2DGuE2Sff6a2JD3Ztw/UserTweets",
+        client,
+        &client.urls.graphql_url("/YNXM2DGuE2Sff6a2JD3Ztw/UserTweets"),
         headers,
         Method::GET,
         Some(json!({
@@ -80,16 +83,17 @@ pub async fn fetch_tweets_and_replies(
     cursor: Option<&str>,
 ) -> Result<QueryTweetsResponse> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let user_id = get_user_id_by_screen_name(client, username).await?;
 
-    let endpoint = Endpoints::user_tweets_and_replies(&user_id, max_tweets.min(40), cursor);
+    let endpoint = Endpoints::user_tweets_and_replies(&client.urls, &user_id, max_tweets.min(40), cursor);
 
     let (value, _headers) =
-        request_api(&client.client, &endpoint.to_request_url(), headers, Method::GET, None).await?;
+        request_api(client, &endpoint.to_request_url(), headers, Method::GET, None).await?;
 
     let parsed_response = parse_timeline_tweets_v2(&value);
+    check_partial_errors(&parsed_response.warnings, parsed_response.tweets.is_empty())?;
     Ok(parsed_response)
 }
 
@@ -100,14 +104,15 @@ pub async fn fetch_tweets_and_replies_by_user_id(
     cursor: Option<&str>,
 ) -> Result<QueryTweetsResponse> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
-    let endpoint = Endpoints::user_tweets_and_replies(user_id, max_tweets.min(40), cursor);
+    let endpoint = Endpoints::user_tweets_and_replies(&client.urls, user_id, max_tweets.min(40), cursor);
 
     let (value, _headers) =
-        request_api(&client.client, &endpoint.to_request_url(), headers, Method::GET, None).await?;
+        request_api(client, &endpoint.to_request_url(), headers, Method::GET, None).await?;
 
     let parsed_response = parse_timeline_tweets_v2(&value);
+    check_partial_errors(&parsed_response.warnings, parsed_response.tweets.is_empty())?;
     Ok(parsed_response)
 }
 
@@ -118,7 +123,7 @@ pub async fn fetch_list_tweets(
     cursor: Option<&str>,
 ) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let mut variables = json!({
         "listId": list_id,
@@ -129,29 +134,45 @@ pub async fn fetch_list_tweets(
         variables["cursor"] = json!(cursor_val);
     }
 
-    let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/LFKj1wqHNTsEJ4Oq7TzaNA/ListLatestTweetsTimeline",
-        headers,
-        Method::GET,
-        Some(json!({
-            "variables": variables,
-            "features": get_default_features()
-        })),
-    )
-    .await?;
+    let url = format!(
+        "{}?variables={}&features={}",
+        client
+            .urls
+            .graphql_url("/LFKj1wqHNTsEJ4Oq7TzaNA/ListLatestTweetsTimeline"),
+        urlencoding::encode(&variables.to_string()),
+        urlencoding::encode(&get_default_features().to_string())
+    );
+
+    let (value, _headers) = request_api(client, &url, headers, Method::GET, None).await?;
 
     Ok(value)
 }
 
+pub async fn fetch_list_timeline(
+    client: &TwitterClient,
+    list_id: &str,
+    max_tweets: i32,
+    cursor: Option<&str>,
+) -> Result<QueryTweetsResponse> {
+    let value = fetch_list_tweets(client, list_id, max_tweets, cursor).await?;
+    let timeline: ListTimeline = serde_json::from_value(value)?;
+
+    let parsed_response = parse_list_timeline_tweets(&timeline);
+    check_partial_errors(&parsed_response.warnings, parsed_response.tweets.is_empty())?;
+    Ok(parsed_response)
+}
+
 pub async fn create_quote_tweet(
     client: &TwitterClient,
     text: &str,
     quoted_tweet_id: &str,
     media_data: Option<Vec<(Vec<u8>, String)>>,
 ) -> Result<Value> {
+    // The quoted tweet's URL is not counted, so only the text is checked.
+    crate::text::validate_length(text, crate::text::MAX_TWEET_LENGTH)?;
+
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let mut variables = json!({
         "tweet_text": text,
@@ -179,8 +200,8 @@ pub async fn create_quote_tweet(
     }
 
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/a1p9RWpkYKBjWv_I3WzS-A/CreateTweet",
+        client,
+        &client.urls.graphql_url("/a1p9RWpkYKBjWv_I3WzS-A/CreateTweet"),
         headers,
         Method::POST,
         Some(json!({
@@ -195,11 +216,11 @@ pub async fn create_quote_tweet(
 
 pub async fn like_tweet(client: &TwitterClient, tweet_id: &str) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/lI07N6Otwv1PhnEgXILM7A/FavoriteTweet",
+        client,
+        &client.urls.graphql_url("/lI07N6Otwv1PhnEgXILM7A/FavoriteTweet"),
         headers,
         Method::POST,
         Some(json!({
@@ -215,11 +236,11 @@ pub async fn like_tweet(client: &TwitterClient, tweet_id: &str) -> Result<Value>
 
 pub async fn retweet(client: &TwitterClient, tweet_id: &str) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/ojPdsZsimiJrUGLR1sjUtA/CreateRetweet",
+        client,
+        &client.urls.graphql_url("/ojPdsZsimiJrUGLR1sjUtA/CreateRetweet"),
         headers,
         Method::POST,
         Some(json!({
@@ -234,14 +255,98 @@ pub async fn retweet(client: &TwitterClient, tweet_id: &str) -> Result<Value> {
     Ok(value)
 }
 
+pub async fn delete_tweet(client: &TwitterClient, tweet_id: &str) -> Result<()> {
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let (value, _headers) = request_api::<Value>(
+        client,
+        &client.urls.graphql_url("/VaenaVgh5q5ih7kvyVjgtg/DeleteTweet"),
+        headers,
+        Method::POST,
+        Some(json!({
+            "variables": {
+                "tweet_id": tweet_id,
+                "dark_request": false
+            }
+        })),
+    )
+    .await?;
+
+    check_mutation(&value, "delete_tweet")
+}
+
+pub async fn unlike_tweet(client: &TwitterClient, tweet_id: &str) -> Result<()> {
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let (value, _headers) = request_api::<Value>(
+        client,
+        &client.urls.graphql_url("/ZYKSe-w7KEslx3JhSIk5LA/UnfavoriteTweet"),
+        headers,
+        Method::POST,
+        Some(json!({
+            "variables": {
+                "tweet_id": tweet_id
+            }
+        })),
+    )
+    .await?;
+
+    check_mutation(&value, "unfavorite_tweet")
+}
+
+pub async fn unretweet(client: &TwitterClient, tweet_id: &str) -> Result<()> {
+    let mut headers = HeaderMap::new();
+    client.install_headers(&mut headers).await?;
+
+    let (value, _headers) = request_api::<Value>(
+        client,
+        &client.urls.graphql_url("/iQtK4dl5hBmXewYZuEOKVw/DeleteRetweet"),
+        headers,
+        Method::POST,
+        Some(json!({
+            "variables": {
+                "source_tweet_id": tweet_id,
+                "dark_request": false
+            }
+        })),
+    )
+    .await?;
+
+    check_mutation(&value, "unretweet")
+}
+
+/// Fails unless the mutation's result field came back, surfacing the
+/// GraphQL errors sent in its place.
+pub(crate) fn check_mutation(value: &Value, field: &str) -> Result<()> {
+    if value["data"].get(field).is_some_and(|result| !result.is_null()) {
+        return Ok(());
+    }
+
+    let errors: Vec<ApiErrorDetail> = value
+        .get("errors")
+        .and_then(|errors| serde_json::from_value(errors.clone()).ok())
+        .unwrap_or_default();
+    if errors.is_empty() {
+        return Err(TwitterError::InvalidResponse(format!(
+            "Response has no {} result",
+            field
+        )));
+    }
+    Err(TwitterError::from_graphql_errors(errors))
+}
+
 pub async fn create_long_tweet(
     client: &TwitterClient,
     text: &str,
     reply_to: Option<&str>,
     media_ids: Option<Vec<String>>,
 ) -> Result<Value> {
+    crate::text::validate_length(text, crate::text::MAX_LONG_TWEET_LENGTH)?;
+
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let mut variables = json!({
         "tweet_text": text,
@@ -270,8 +375,8 @@ pub async fn create_long_tweet(
     }
 
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/YNXM2DGuE2Sff6a2JD3Ztw/CreateNoteTweet",
+        client,
+        &client.urls.graphql_url("/YNXM2DGuE2Sff6a2JD3Ztw/CreateNoteTweet"),
         headers,
         Method::POST,
         Some(json!({
@@ -291,7 +396,7 @@ pub async fn fetch_liked_tweets(
     cursor: Option<&str>,
 ) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     let mut variables = json!({
         "userId": user_id,
@@ -304,8 +409,8 @@ pub async fn fetch_liked_tweets(
     }
 
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/YlkSUg4Czo2Zx7yRqpwDow/Likes",
+        client,
+        &client.urls.graphql_url("/YlkSUg4Czo2Zx7yRqpwDow/Likes"),
         headers,
         Method::GET,
         Some(json!({
@@ -324,9 +429,9 @@ pub async fn upload_media(
     media_type: &str,
 ) -> Result<String> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
-    let upload_url = "https://upload.twitter.com/1.1/media/upload.json";
+    let upload_url = client.urls.upload_url("/1.1/media/upload.json");
 
     // Check if media is video
     let is_video = media_type.starts_with("video/");
@@ -336,10 +441,12 @@ pub async fn upload_media(
         upload_video_in_chunks(client, file_data, media_type, headers).await
     } else {
         // Handle image upload directly
-        let form = reqwest::multipart::Form::new()
-            .part("media", reqwest::multipart::Part::bytes(file_data));
+        let form = || {
+            reqwest::multipart::Form::new()
+                .part("media", reqwest::multipart::Part::bytes(file_data.clone()))
+        };
 
-        let (response, _) = request_multipart_api::<Value>(&client.client, upload_url, headers, form).await?;
+        let (response, _) = request_multipart_api::<Value>(client, &upload_url, headers, form).await?;
 
         response["media_id_string"]
             .as_str()
@@ -354,12 +461,12 @@ async fn upload_video_in_chunks(
     media_type: &str,
     headers: HeaderMap,
 ) -> Result<String> {
-    let upload_url = "https://upload.twitter.com/1.1/media/upload.json";
+    let upload_url = client.urls.upload_url("/1.1/media/upload.json");
 
     // INIT command
     let (init_response, _) = request_api::<Value>(
-        &client.client,
-        upload_url,
+        client,
+        &upload_url,
         headers.clone(),
         Method::POST,
         Some(json!({
@@ -377,23 +484,21 @@ async fn upload_video_in_chunks(
 
     // APPEND command - upload in chunks
     let chunk_size = 5 * 1024 * 1024; // 5MB chunks
-    let mut segment_index = 0;
-
-    for chunk in file_data.chunks(chunk_size) {
-        let form = reqwest::multipart::Form::new()
-            .text("command", "APPEND")
-            .text("media_id", media_id.clone())
-            .text("segment_index", segment_index.to_string())
-            .part("media", reqwest::multipart::Part::bytes(chunk.to_vec()));
-
-        let (_, _) = request_multipart_api::<Value>(&client.client, upload_url, headers.clone(), form).await?;
-
-        segment_index += 1;
+    for (segment_index, chunk) in file_data.chunks(chunk_size).enumerate() {
+        let form = || {
+            reqwest::multipart::Form::new()
+                .text("command", "APPEND")
+                .text("media_id", media_id.clone())
+                .text("segment_index", segment_index.to_string())
+                .part("media", reqwest::multipart::Part::bytes(chunk.to_vec()))
+        };
+
+        let (_, _) = request_multipart_api::<Value>(client, &upload_url, headers.clone(), form).await?;
     }
 
     // FINALIZE command
     let (finalize_response, _) = request_api::<Value>(
-        &client.client,
+        client,
         &format!("{}?command=FINALIZE&media_id={}", upload_url, media_id),
         headers.clone(),
         Method::POST,
@@ -410,14 +515,14 @@ async fn upload_video_in_chunks(
 }
 
 async fn check_upload_status(client: &TwitterClient, media_id: &str, headers: &HeaderMap) -> Result<()> {
-    let upload_url = "https://upload.twitter.com/1.1/media/upload.json";
+    let upload_url = client.urls.upload_url("/1.1/media/upload.json");
 
     for _ in 0..20 {
         // Maximum 20 attempts
         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await; // Wait 5 seconds
 
         let (status_response, _) = request_api::<Value>(
-            &client.client,
+            client,
             &format!("{}?command=STATUS&media_id={}", upload_url, media_id),
             headers.clone(),
             Method::GET,
@@ -437,17 +542,32 @@ async fn check_upload_status(client: &TwitterClient, media_id: &str, headers: &H
     Err(TwitterError::Api("Video processing timeout".into()))
 }
 
-pub async fn get_tweet(client: &TwitterClient, id: &str) -> Result<Tweet> {
+/// A tweet fetched with `get_tweet`. `warnings` holds GraphQL errors returned
+/// alongside it, e.g. for parts of the conversation that could not be loaded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TweetDetail {
+    pub tweet: Tweet,
+    #[serde(default)]
+    pub warnings: Vec<ApiErrorDetail>,
+}
+
+pub async fn get_tweet(client: &TwitterClient, id: &str) -> Result<TweetDetail> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
-    let tweet_detail_request = Endpoints::tweet_detail(id);
+    client.install_headers(&mut headers).await?;
+    let tweet_detail_request = Endpoints::tweet_detail(&client.urls, id);
     let url = tweet_detail_request.to_request_url();
 
-    let (response, _) = request_api::<Value>(&client.client, &url, headers, Method::GET, None).await?;
+    let (response, _) = request_api::<Value>(client, &url, headers, Method::GET, None).await?;
     let data = response.clone();
     let conversation: ThreadedConversation = serde_json::from_value(data)?;
-    let tweets = parse_threaded_conversation(&conversation);
-    tweets.into_iter().next().ok_or_else(|| TwitterError::Api("No tweets found".into()))
+    let errors = conversation.errors.clone().unwrap_or_default();
+    let tweet = parse_threaded_conversation(&conversation);
+    check_partial_errors(&errors, tweet.is_none())?;
+    let tweet = tweet.ok_or_else(|| TwitterError::Api("No tweets found".into()))?;
+    Ok(TweetDetail {
+        tweet,
+        warnings: errors,
+    })
 }
 
 fn create_tweet_features() -> Value {
@@ -564,9 +684,33 @@ pub async fn create_tweet_request(
     text: &str,
     reply_to: Option<&str>,
     media_data: Option<Vec<(Vec<u8>, String)>>,
+) -> Result<Value> {
+    crate::text::validate_tweet(text, media_data.as_ref().is_some_and(|media| !media.is_empty()))?;
+    send_create_tweet(client, text, reply_to, media_data, None).await
+}
+
+/// Posts a tweet with a poll. The poll card is created first and attached
+/// by its `card_uri`.
+pub async fn create_poll_tweet(
+    client: &TwitterClient,
+    text: &str,
+    reply_to: Option<&str>,
+    poll: &NewPoll,
+) -> Result<Value> {
+    crate::text::validate_tweet(text, false)?;
+    let card_uri = create_poll_card(client, poll).await?;
+    send_create_tweet(client, text, reply_to, None, Some(card_uri)).await
+}
+
+async fn send_create_tweet(
+    client: &TwitterClient,
+    text: &str,
+    reply_to: Option<&str>,
+    media_data: Option<Vec<(Vec<u8>, String)>>,
+    card_uri: Option<String>,
 ) -> Result<Value> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
     // Prepare variables
     let mut variables = json!({
@@ -601,11 +745,15 @@ pub async fn create_tweet_request(
 
         variables["media"]["media_entities"] = json!(media_entities);
     }
+
+    if let Some(card_uri) = card_uri {
+        variables["card_uri"] = json!(card_uri);
+    }
     let features = create_tweet_features();
     // Make the create tweet request
     let (value, _headers) = request_api(
-        &client.client,
-        "https://twitter.com/i/api/graphql/a1p9RWpkYKBjWv_I3WzS-A/CreateTweet",
+        client,
+        &client.urls.graphql_url("/a1p9RWpkYKBjWv_I3WzS-A/CreateTweet"),
         headers,
         Method::POST,
         Some(json!({
@@ -619,6 +767,70 @@ pub async fn create_tweet_request(
     Ok(value)
 }
 
+/// One tweet of a thread posted with `Scraper::send_thread`.
+#[derive(Debug, Clone, Default)]
+pub struct ThreadPart {
+    pub text: String,
+    pub media: Option<Vec<(Vec<u8>, String)>>,
+}
+
+impl ThreadPart {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            media: None,
+        }
+    }
+
+    pub fn with_media(mut self, data: Vec<u8>, media_type: impl Into<String>) -> Self {
+        self.media
+            .get_or_insert_with(Vec::new)
+            .push((data, media_type.into()));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ThreadOptions {
+    /// Posts the first part as a reply to this tweet.
+    pub reply_to: Option<String>,
+    /// Deletes the parts already posted when a later one fails.
+    pub rollback_on_failure: bool,
+}
+
+impl ThreadOptions {
+    pub fn with_reply_to(mut self, tweet_id: impl Into<String>) -> Self {
+        self.reply_to = Some(tweet_id.into());
+        self
+    }
+
+    pub fn with_rollback_on_failure(mut self, rollback: bool) -> Self {
+        self.rollback_on_failure = rollback;
+        self
+    }
+}
+
+/// Reads the new tweet's ID from a CreateTweet or CreateNoteTweet response.
+pub fn created_tweet_id(value: &Value) -> Result<String> {
+    let data = &value["data"];
+    let result = data
+        .get("create_tweet")
+        .or_else(|| data.get("notetweet_create"))
+        .map(|created| &created["tweet_results"]["result"]);
+
+    result
+        .and_then(|result| {
+            result["rest_id"]
+                .as_str()
+                .or_else(|| result["tweet"]["rest_id"].as_str())
+        })
+        .map(String::from)
+        .ok_or_else(|| match check_mutation(value, "create_tweet") {
+            Err(err) => err,
+            Ok(()) => TwitterError::InvalidResponse("Created tweet has no rest_id".into()),
+        })
+}
+
 fn create_quote_tweet_features() -> Value {
     json!({
         "interactive_text_enabled": true,
@@ -667,13 +879,110 @@ pub async fn fetch_user_tweets(
     
 ) -> Result<QueryTweetsResponse> {
     let mut headers = HeaderMap::new();
-    client.auth.install_headers(&mut headers).await?;
+    client.install_headers(&mut headers).await?;
 
-    let endpoint = Endpoints::user_tweets(user_id, max_tweets.min(200), cursor);
+    let endpoint = Endpoints::user_tweets(&client.urls, user_id, max_tweets.min(200), cursor);
 
     let (value, _headers) =
-        request_api(&client.client, &endpoint.to_request_url(), headers, Method::GET, None).await?;
+        request_api(client, &endpoint.to_request_url(), headers, Method::GET, None).await?;
 
     let parsed_response = parse_timeline_tweets_v2(&value);
+    check_partial_errors(&parsed_response.warnings, parsed_response.tweets.is_empty())?;
     Ok(parsed_response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiErrorKind;
+
+    #[test]
+    fn mutation_accepts_done_string() {
+        let value = json!({ "data": { "unfavorite_tweet": "Done" } });
+        assert!(check_mutation(&value, "unfavorite_tweet").is_ok());
+    }
+
+    #[test]
+    fn mutation_accepts_result_object() {
+        let value = json!({ "data": { "delete_tweet": { "tweet_results": {} } } });
+        assert!(check_mutation(&value, "delete_tweet").is_ok());
+    }
+
+    #[test]
+    fn mutation_without_field_is_invalid_response() {
+        let value = json!({ "data": {} });
+        assert!(matches!(
+            check_mutation(&value, "delete_tweet"),
+            Err(TwitterError::InvalidResponse(_))
+        ));
+
+        let value = json!({ "data": { "delete_tweet": null } });
+        assert!(matches!(
+            check_mutation(&value, "delete_tweet"),
+            Err(TwitterError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn mutation_with_null_data_surfaces_errors() {
+        let value = json!({
+            "data": null,
+            "errors": [{ "code": 144, "message": "No status found with that ID." }]
+        });
+        let err = check_mutation(&value, "delete_tweet").unwrap_err();
+        assert_eq!(err.kind(), Some(ApiErrorKind::TweetNotFound));
+        assert_eq!(err.status(), Some(200));
+    }
+
+    #[test]
+    fn reads_created_tweet_id() {
+        let value = json!({
+            "data": {
+                "create_tweet": {
+                    "tweet_results": {
+                        "result": {
+                            "rest_id": "1850000000000000001",
+                            "legacy": { "full_text": "hello" }
+                        }
+                    }
+                }
+            }
+        });
+        assert_eq!(created_tweet_id(&value).unwrap(), "1850000000000000001");
+    }
+
+    #[test]
+    fn reads_note_tweet_and_visibility_wrapped_ids() {
+        let value = json!({
+            "data": {
+                "notetweet_create": {
+                    "tweet_results": {
+                        "result": {
+                            "__typename": "TweetWithVisibilityResults",
+                            "tweet": { "rest_id": "1850000000000000002" }
+                        }
+                    }
+                }
+            }
+        });
+        assert_eq!(created_tweet_id(&value).unwrap(), "1850000000000000002");
+    }
+
+    #[test]
+    fn created_tweet_id_surfaces_errors() {
+        let value = json!({
+            "data": { "create_tweet": { "tweet_results": {} } }
+        });
+        assert!(matches!(
+            created_tweet_id(&value),
+            Err(TwitterError::InvalidResponse(_))
+        ));
+
+        let value = json!({
+            "data": {},
+            "errors": [{ "code": 187, "message": "Status is a duplicate." }]
+        });
+        let err = created_tweet_id(&value).unwrap_err();
+        assert_eq!(err.kind(), Some(ApiErrorKind::DuplicateTweet));
+    }
+}